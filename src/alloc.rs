@@ -0,0 +1,122 @@
+use crate::human::human_bytes;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+thread_local! {
+    /// 当前线程的分配次数。
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+    /// 当前线程分配的字节数。
+    static ALLOCATED_BYTES: Cell<u64> = const { Cell::new(0) };
+}
+
+/// [`CountingAllocator`] 是否已被设置为全局分配器（即是否处理过至少一次分配）。
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// 统计每个线程分配次数与字节数的全局分配器，供 [`c_alloc!`](crate::c_alloc) 使用（需要启用 `alloc-count` 特性）。
+///
+/// 实际的分配交给系统分配器完成，每次分配只会额外更新两个线程局部的计数器。需要在程序中通过
+/// `#[global_allocator]` 设置后才会生效，未设置时 [`c_alloc!`](crate::c_alloc) 无法统计分配。
+/// 已经使用了其他全局分配器（如 jemalloc）的程序无法同时使用该分配器。
+///
+/// # 示例
+/// ```rust
+/// use clogger::CountingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+/// ```
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    /// 创建一个统计分配的全局分配器。
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        CountingAllocator
+    }
+}
+
+/// 记录当前线程的一次分配。
+fn record(size: usize) {
+    // 线程退出时线程局部变量可能已被销毁，此时不再统计
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    let _ = ALLOCATED_BYTES.try_with(|bytes| bytes.set(bytes.get() + size as u64));
+    // 先读取再写入，避免每次分配都写入同一个缓存行，使其在多个 CPU 核心之间来回同步
+    if !INSTALLED.load(Ordering::Relaxed) {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // 只统计增加的字节数，缩小时不计字节
+        record(new_size.saturating_sub(layout.size()));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// 某一时刻当前线程的分配统计，供 [`c_alloc!`](crate::c_alloc) 计算代码块中的分配。
+#[doc(hidden)]
+pub struct AllocSnapshot {
+    allocations: u64,
+    bytes: u64,
+}
+
+impl AllocSnapshot {
+    /// 记录当前线程此刻的分配统计。
+    pub fn now() -> Self {
+        AllocSnapshot {
+            allocations: ALLOCATIONS.with(Cell::get),
+            bytes: ALLOCATED_BYTES.with(Cell::get),
+        }
+    }
+
+    /// 生成自记录以来当前线程的分配次数与字节数的说明。
+    pub fn describe_since(&self, label: &str) -> String {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            return format!(
+                "{}: 未设置 CountingAllocator 为全局分配器，无法统计分配",
+                label
+            );
+        }
+        let now = AllocSnapshot::now();
+        format!(
+            "{}: {} 次分配，共 {}",
+            label,
+            now.allocations - self.allocations,
+            human_bytes(now.bytes - self.bytes)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting() {
+        let allocator = CountingAllocator::new();
+        let snapshot = AllocSnapshot::now();
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        unsafe {
+            let ptr = allocator.alloc(layout);
+            let ptr = allocator.realloc(ptr, layout, 128);
+            allocator.dealloc(ptr, Layout::from_size_align(128, 8).unwrap());
+        }
+        assert_eq!(snapshot.describe_since("测试"), "测试: 2 次分配，共 128 B");
+    }
+}
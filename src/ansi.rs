@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+
+/// 转义字符（ESC）。
+const ESC: char = '\x1b';
+
+/// 字符串终止符（ST）的 8 位形式。
+const ST: char = '\u{9c}';
+
+/// 移除文本中的 ANSI 转义序列。
+///
+/// 除颜色等 SGR 序列外，还会移除所有 CSI 序列（如光标移动、清屏）、OSC 序列（如 OSC 8 超链接、窗口标题）、
+/// DCS / SOS / PM / APC 等字符串序列以及其他两字节的转义序列，8 位的 C1 形式（如 `\u{9b}`）同样会被移除。
+/// 不完整的序列（如文本末尾缺少结束符的序列）会被移除到文本末尾，避免残留的转义字符影响之后的输出。
+///
+/// 写入日志文件时，CLogger 默认使用该函数处理日志内容，见 [`CloggerBuilder::file_ansi_stripper`](crate::CloggerBuilder::file_ansi_stripper)。
+///
+/// # 示例
+/// ```rust
+/// use clogger::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1b[1;31m错误\x1b[0m"), "错误");
+/// assert_eq!(
+///     strip_ansi("详见 \x1b]8;;https://example.com\x1b\\文档\x1b]8;;\x1b\\"),
+///     "详见 文档"
+/// );
+/// ```
+///
+/// # 参数
+/// - `text`: 需要处理的文本。
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains(|c| c == ESC || ('\u{80}'..='\u{9f}').contains(&c)) {
+        return Cow::Borrowed(text);
+    }
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        let kind = match c {
+            ESC => match chars.next() {
+                Some('[') => Sequence::Csi,
+                Some(']') => Sequence::Osc,
+                Some('P' | 'X' | '^' | '_') => Sequence::String,
+                // 以中间字节（0x20–0x2F）开头的序列在遇到最终字节（0x30–0x7E）时结束
+                Some('\x20'..='\x2f') => Sequence::Escape,
+                // 其他两字节的序列（如 `ESC 7`、`ESC c`）
+                Some('\x30'..='\x7e') | None => continue,
+                // 非法的序列只移除转义字符本身
+                Some(other) => {
+                    stripped.push(other);
+                    continue;
+                }
+            },
+            '\u{9b}' => Sequence::Csi,
+            '\u{9d}' => Sequence::Osc,
+            '\u{90}' | '\u{98}' | '\u{9e}' | '\u{9f}' => Sequence::String,
+            c => {
+                stripped.push(c);
+                continue;
+            }
+        };
+        kind.skip(&mut chars);
+    }
+    Cow::Owned(stripped)
+}
+
+/// 需要跳过参数部分的转义序列的类型。
+enum Sequence {
+    /// 控制序列（CSI），在最终字节（0x40–0x7E）处结束。
+    Csi,
+    /// 操作系统命令（OSC），在 BEL 或 ST 处结束。
+    Osc,
+    /// DCS、SOS、PM 与 APC 等字符串序列，在 ST 处结束。
+    String,
+    /// 带有中间字节的转义序列，在最终字节（0x30–0x7E）处结束。
+    Escape,
+}
+
+impl Sequence {
+    /// 跳过序列的剩余部分（包括结束符），没有结束符时跳过所有剩余的文本。
+    fn skip(&self, chars: &mut std::str::Chars) {
+        while let Some(c) = chars.next() {
+            let end = match self {
+                Sequence::Csi => ('\x40'..='\x7e').contains(&c),
+                Sequence::Osc => c == '\x07' || c == ST || (c == ESC && ends_with_st(chars)),
+                Sequence::String => c == ST || (c == ESC && ends_with_st(chars)),
+                Sequence::Escape => ('\x30'..='\x7e').contains(&c),
+            };
+            if end {
+                return;
+            }
+        }
+    }
+}
+
+/// 判断 ESC 之后是否为 `\`（即 7 位形式的 ST），是则一并跳过。
+fn ends_with_st(chars: &mut std::str::Chars) -> bool {
+    if chars.as_str().starts_with('\\') {
+        chars.next();
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert!(matches!(strip_ansi("没有转义序列"), Cow::Borrowed(_)));
+        // 嵌套与相邻的序列
+        assert_eq!(
+            strip_ansi("\x1b[1m\x1b[31m错\x1b[4;38;5;208m误\x1b[0m\x1b[0m"),
+            "错误"
+        );
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1G进度 100%"), "进度 100%");
+        // 两种结束符的 OSC 序列
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x07链接\x1b]8;;\x1b\\ \x1b]0;标题\x07"),
+            "链接 "
+        );
+        assert_eq!(strip_ansi("\x1bP1$r\x1b\\A\x1b(B\x1b7B\x1b8"), "AB");
+        // 8 位的 C1 形式
+        assert_eq!(strip_ansi("\u{9b}31m红色\u{9b}0m"), "红色");
+        // 不完整的序列
+        assert_eq!(strip_ansi("被截断的\x1b[38;5"), "被截断的");
+        assert_eq!(strip_ansi("被截断的\x1b]8;;https://"), "被截断的");
+        assert_eq!(strip_ansi("末尾的\x1b"), "末尾的");
+        assert_eq!(strip_ansi("\x1b\n换行"), "\n换行");
+    }
+}
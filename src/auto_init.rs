@@ -0,0 +1,104 @@
+#[cfg(feature = "auto-init")]
+use crate::{file::ACTIVE_FILE, toggle::is_initialized, CloggerBuilder};
+#[cfg(any(feature = "auto-init", test))]
+use std::path::{Path, PathBuf};
+
+/// 全局 CLogger 尚未初始化时，以默认的日志文件自动初始化（需要启用 `auto-init` 特性）。
+///
+/// 由 CLogger 的日志宏在输出日志之前调用。日志文件的位置由 [`default_log_path`] 决定，
+/// 初始化完成后会以 `Debug` 级别输出一条说明日志文件位置的日志。
+/// 多个线程同时触发时只会初始化一次，其余线程等待初始化完成后继续输出，不会输出重复初始化的警告。
+#[cfg(feature = "auto-init")]
+#[doc(hidden)]
+pub fn auto_init() {
+    if is_initialized() {
+        return;
+    }
+    let app = app_name();
+    let path = default_log_path(|name| std::env::var(name).ok(), &app);
+    // 无法创建目录时退回到临时目录
+    let path = match path.parent().map(std::fs::create_dir_all) {
+        Some(Err(_)) => std::env::temp_dir().join(format!("{}.log", app)),
+        _ => path,
+    };
+    if CloggerBuilder::new()
+        .log_file(&path.to_string_lossy())
+        .init_once()
+    {
+        // `CLOGGER_FILE` 环境变量可能覆盖了默认的日志文件
+        let active = ACTIVE_FILE
+            .get()
+            .and_then(|file| file.path())
+            .unwrap_or(path);
+        c_debug!(format!(
+            "未调用 init_clogger，CLogger 已自动初始化，日志文件为 {}",
+            active.display()
+        ));
+    }
+}
+
+/// 未启用 `auto-init` 特性时不做任何事情。
+#[cfg(not(feature = "auto-init"))]
+#[doc(hidden)]
+#[inline(always)]
+pub fn auto_init() {}
+
+/// 获取当前程序的名称（可执行文件名去掉扩展名），无法获取时为 `clogger`。
+#[cfg(feature = "auto-init")]
+fn app_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "clogger".to_string())
+}
+
+/// 获取自动初始化时使用的日志文件位置，`var` 用于读取环境变量。
+///
+/// 依次尝试 `$XDG_STATE_HOME/<程序名称>/app.log` 与 `$HOME/.local/state/<程序名称>/app.log`
+/// （XDG 规范中 `XDG_STATE_HOME` 的默认值），均不可用时使用临时目录中的 `<程序名称>.log`。
+#[cfg(any(feature = "auto-init", test))]
+fn default_log_path(var: impl Fn(&str) -> Option<String>, app: &str) -> PathBuf {
+    // XDG 规范要求忽略相对路径
+    let absolute = |name| var(name).map(PathBuf::from).filter(|dir| dir.is_absolute());
+    let state_home = absolute("XDG_STATE_HOME")
+        .or_else(|| absolute("HOME").map(|home| home.join(Path::new(".local/state"))));
+    match state_home {
+        Some(dir) => dir.join(app).join("app.log"),
+        None => std::env::temp_dir().join(format!("{}.log", app)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_log_path() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            default_log_path(
+                env(&[("XDG_STATE_HOME", "/state"), ("HOME", "/home/a")]),
+                "app"
+            ),
+            Path::new("/state/app/app.log")
+        );
+        // 相对路径会被忽略
+        assert_eq!(
+            default_log_path(
+                env(&[("XDG_STATE_HOME", "state"), ("HOME", "/home/a")]),
+                "app"
+            ),
+            Path::new("/home/a/.local/state/app/app.log")
+        );
+        assert_eq!(
+            default_log_path(env(&[]), "app"),
+            std::env::temp_dir().join("app.log")
+        );
+    }
+}
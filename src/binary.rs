@@ -0,0 +1,248 @@
+use crate::file::{FileOptions, LogFile, LogFileWriter};
+use crate::format::{capped_message, record_time, split_target, Clock};
+use chrono::{DateTime, Local, Utc};
+use log::Level;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// 单条日志中除长度前缀外的固定部分的长度：时间戳（8 字节）、级别（1 字节）与两个长度前缀（各 4 字节）。
+const FIXED_LEN: usize = 8 + 1 + 4 + 4;
+
+/// 将日志以紧凑的二进制格式写入文件的输出端。
+///
+/// 每条日志的格式为（整数均为小端序）：
+/// - `u32`: 之后内容的总长度；
+/// - `u64`: 时间戳（Unix 纪元以来的纳秒数）；
+/// - `u8`: 日志级别（`1` 为 `Error`，`5` 为 `Trace`）；
+/// - `u32` + UTF-8: 模块名称；
+/// - `u32` + UTF-8: 日志信息内容。
+///
+/// 写入时不进行文本格式化，可以通过 [`decode_binary_log`] 解码。
+pub(crate) struct BinarySink {
+    file: Arc<LogFile>,
+    writer: Mutex<LogFileWriter>,
+    clock: Option<Clock>,
+    max_record_len: usize,
+}
+
+impl BinarySink {
+    /// 以追加模式打开 `path` 处的文件（不存在时自动创建）。
+    ///
+    /// 二进制格式不支持回绕写入，`options` 中的回绕与 BOM 设置会被忽略。
+    /// 日志信息内容最多保留 `max_record_len` 字节。
+    pub(crate) fn open(
+        path: &Path,
+        options: FileOptions,
+        clock: Option<Clock>,
+        max_record_len: usize,
+    ) -> io::Result<Self> {
+        let options = FileOptions {
+            wrap_size: None,
+            utf8_bom: false,
+            ..options
+        };
+        let file = Arc::new(LogFile::open(path, options)?);
+        Ok(BinarySink {
+            writer: Mutex::new(LogFileWriter::new(file.clone())),
+            file,
+            clock,
+            max_record_len,
+        })
+    }
+}
+
+impl log::Log for BinarySink {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let now = match (record_time(record), &self.clock) {
+            (Some(time), _) => time,
+            (None, Some(clock)) => clock(),
+            (None, None) => Local::now(),
+        };
+        let (module, _) = split_target(record.target());
+        let message = capped_message(record.args(), self.max_record_len);
+        let bytes = encode(
+            now.timestamp_nanos_opt().unwrap_or_default() as u64,
+            record.level(),
+            module,
+            &message,
+        );
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writer.write_all(&bytes).and_then(|_| writer.flush()) {
+            eprintln!("CLogger 写入二进制日志时出现错误: {}", e);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = self.file.flush();
+    }
+}
+
+/// 将一条日志编码为二进制格式，格式见 [`BinarySink`]。
+fn encode(timestamp: u64, level: Level, target: &str, message: &str) -> Vec<u8> {
+    let len = FIXED_LEN + target.len() + message.len();
+    let mut bytes = Vec::with_capacity(4 + len);
+    bytes.extend_from_slice(&(len as u32).to_le_bytes());
+    bytes.extend_from_slice(&timestamp.to_le_bytes());
+    bytes.push(level as u8);
+    bytes.extend_from_slice(&(target.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(target.as_bytes());
+    bytes.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(message.as_bytes());
+    bytes
+}
+
+/// 由 [`decode_binary_log`] 解码得到的一条日志。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryRecord {
+    /// 日志的时间戳。
+    pub timestamp: DateTime<Utc>,
+    /// 日志级别。
+    pub level: Level,
+    /// 模块名称（不包含位置信息）。
+    pub target: String,
+    /// 日志信息内容。
+    pub message: String,
+}
+
+/// 解码由 [`CloggerBuilder::binary_file`](crate::CloggerBuilder::binary_file) 写入的二进制日志（需要启用 `binary` 特性）。
+///
+/// 返回的迭代器会依次读取每条日志，读到文件末尾时结束。若最后一条日志不完整（例如进程在写入时崩溃），
+/// 或者内容不符合格式，迭代器会返回一个错误，之后不再继续读取。`reader` 无需额外缓冲。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_warn, decode_binary_log, CloggerBuilder};
+/// use std::fs::File;
+///
+/// let logger = CloggerBuilder::new()
+///     .binary_file("/tmp/clogger_example.bin")
+///     .build();
+/// c_warn!(logger => "example::moudle_name", "磁盘空间不足");
+///
+/// for record in decode_binary_log(File::open("/tmp/clogger_example.bin").unwrap()) {
+///     let record = record.unwrap();
+///     println!("{} [{}] [{}] {}", record.timestamp, record.level, record.target, record.message);
+/// }
+/// ```
+///
+/// # 参数
+/// - `reader`: 二进制日志的来源。
+pub fn decode_binary_log<R: Read>(reader: R) -> impl Iterator<Item = io::Result<BinaryRecord>> {
+    let mut reader = io::BufReader::new(reader);
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        let result = decode_record(&mut reader).transpose();
+        failed = matches!(result, Some(Err(_)));
+        result
+    })
+}
+
+/// 读取并解码一条日志，在日志的边界处读到文件末尾时返回 `None`。
+fn decode_record(reader: &mut impl Read) -> io::Result<Option<BinaryRecord>> {
+    let mut len = [0; 4];
+    match reader.read(&mut len[..1])? {
+        0 => return Ok(None),
+        _ => reader.read_exact(&mut len[1..])?,
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    if len < FIXED_LEN {
+        return Err(invalid_data("日志的长度过短"));
+    }
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+
+    let (timestamp, rest) = bytes.split_at(8);
+    let timestamp = u64::from_le_bytes(timestamp.try_into().unwrap());
+    let level = match rest[0] {
+        1 => Level::Error,
+        2 => Level::Warn,
+        3 => Level::Info,
+        4 => Level::Debug,
+        5 => Level::Trace,
+        _ => return Err(invalid_data("未知的日志级别")),
+    };
+    let (target, rest) = split_string(&rest[1..])?;
+    let (message, rest) = split_string(rest)?;
+    if !rest.is_empty() {
+        return Err(invalid_data("日志的长度与内容不符"));
+    }
+    Ok(Some(BinaryRecord {
+        timestamp: DateTime::from_timestamp_nanos(timestamp as i64),
+        level,
+        target,
+        message,
+    }))
+}
+
+/// 从 `bytes` 开头读取一个带长度前缀的字符串，返回字符串与剩余的内容。
+fn split_string(bytes: &[u8]) -> io::Result<(String, &[u8])> {
+    let (len, rest) = bytes
+        .split_first_chunk::<4>()
+        .ok_or_else(|| invalid_data("日志的长度与内容不符"))?;
+    let len = u32::from_le_bytes(*len) as usize;
+    if rest.len() < len {
+        return Err(invalid_data("日志的长度与内容不符"));
+    }
+    let (string, rest) = rest.split_at(len);
+    let string = String::from_utf8(string.to_vec()).map_err(|e| invalid_data(&e.to_string()))?;
+    Ok((string, rest))
+}
+
+/// 创建表示格式错误的 [`io::Error`]。
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("无法解码二进制日志: {}", message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut bytes = encode(
+            1_717_243_200_123_456_789,
+            Level::Warn,
+            "app::net",
+            "连接超时",
+        );
+        bytes.extend(encode(0, Level::Trace, "", ""));
+        let records: Vec<_> = decode_binary_log(bytes.as_slice())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].timestamp.timestamp_nanos_opt(),
+            Some(1_717_243_200_123_456_789)
+        );
+        assert_eq!(records[0].level, Level::Warn);
+        assert_eq!(records[0].target, "app::net");
+        assert_eq!(records[0].message, "连接超时");
+        assert_eq!(records[1].level, Level::Trace);
+        assert_eq!(records[1].message, "");
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut bytes = encode(0, Level::Info, "app", "完整的日志");
+        let complete = bytes.len();
+        bytes.extend(encode(0, Level::Info, "app", "被截断的日志"));
+        bytes.truncate(complete + 10);
+
+        let mut records = decode_binary_log(bytes.as_slice());
+        assert_eq!(records.next().unwrap().unwrap().message, "完整的日志");
+        let error = records.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(records.next().is_none());
+    }
+}
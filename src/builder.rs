@@ -0,0 +1,3022 @@
+use crate::ansi::strip_ansi;
+#[cfg(feature = "binary")]
+use crate::binary::BinarySink;
+#[cfg(feature = "test-util")]
+use crate::capture::CaptureSink;
+use crate::counter::{set_shutdown_summary, LevelCounter, Summary};
+use crate::dedupe::Dedupe;
+use crate::early::set_global_logger;
+#[cfg(all(windows, feature = "windows-eventlog"))]
+use crate::eventlog::EventLogSink;
+use crate::file::{
+    register_file, spawn_flusher, FileOptions, FileOutput, LogFile, LogFileWriter, ACTIVE_FILE,
+};
+use crate::format::{
+    capped_message, set_relative_location, set_target_shown, split_target, AnsiStripper, Clock,
+    FieldMask, Format, TimestampMode, DEFAULT_MAX_RECORD_LEN,
+};
+#[cfg(all(target_os = "linux", feature = "journald"))]
+use crate::journald::{JournaldSink, JOURNAL_SOCKET};
+use crate::levels::{set_global_levels, SinkLevels};
+use crate::logger::Clogger;
+use crate::matrix::{DestinationKind, SinkFormat, SinkMatrix};
+#[cfg(test)]
+use crate::memory::MemoryWriter;
+use crate::meta::{schema_object, set_file_output, set_file_schema};
+use crate::pipe::BrokenPipeGuard;
+use crate::progress::{ProgressWriter, Redraw};
+use crate::snapshot::{set_current_config, CloggerConfig};
+#[cfg(unix)]
+use crate::socket::UnixSocketSink;
+#[cfg(feature = "sqlite")]
+use crate::sqlite::SqliteSink;
+use crate::theme::ColorTheme;
+use crate::toggle::{self, is_enabled};
+use crate::verbose::effective_level;
+use crate::worker::spawn_writer;
+use chrono::{DateTime, Local};
+use fern::{Dispatch, Output};
+use log::{Level, LevelFilter};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
+
+/// CLogger 的构建器。
+///
+/// 通过链式调用对 CLogger 进行配置，最后调用 [`CloggerBuilder::init`] 完成初始化。
+/// [`init_clogger`](crate::init_clogger) 即是该构建器在默认配置下的简写。
+///
+/// # 示例
+/// ```rust
+/// use clogger::CloggerBuilder;
+/// use log::{Level, LevelFilter};
+///
+/// CloggerBuilder::new()
+///     .log_file("/tmp/clogger_example.log")
+///     .level(LevelFilter::Info)
+///     .init();
+/// ```
+pub struct CloggerBuilder {
+    log_file_path: Option<String>,
+    log_file_handle: Option<File>,
+    level: LevelFilter,
+    plain: bool,
+    /// 初始化提示，`None` 表示使用默认的提示，`Some(None)` 表示不输出提示。
+    startup_message: Option<Option<String>>,
+    startup_level: Level,
+    app_tag: Option<String>,
+    non_blocking: bool,
+    ordered: bool,
+    dedupe_consecutive: bool,
+    progress_redraw: Option<Redraw>,
+    print_summary: bool,
+    clock: Option<Clock>,
+    timestamp_mode: TimestampMode,
+    theme: ColorTheme,
+    sanitize_control: bool,
+    filters: Vec<MetadataFilter>,
+    record_filters: Vec<RecordFilterFn>,
+    message_transform: Option<MessageTransformFn>,
+    stdout_level: LevelFilter,
+    stderr_level: Option<LevelFilter>,
+    routes: [Sink; 5],
+    file_level: LevelFilter,
+    file_options: FileOptions,
+    file_ansi_stripper: Option<AnsiStripper>,
+    target_files: Vec<TargetFile>,
+    level_files: Vec<(Level, String)>,
+    sink_matrix: Option<SinkMatrix>,
+    sync_level: Option<Level>,
+    target_width: Option<usize>,
+    separator: Option<String>,
+    show_crate_version: bool,
+    show_location: bool,
+    console_location: Option<bool>,
+    file_location: Option<bool>,
+    show_target: bool,
+    relative_location: bool,
+    max_record_len: Option<usize>,
+    global_fields: Vec<(String, String)>,
+    masked_fields: Vec<String>,
+    mask_case_sensitive: bool,
+    console_capture_path: Option<String>,
+    #[cfg(feature = "binary")]
+    binary_path: Option<String>,
+    #[cfg(feature = "sqlite")]
+    sqlite_path: Option<String>,
+    #[cfg(feature = "sqlite")]
+    sqlite_flat_location: bool,
+    #[cfg(all(target_os = "linux", feature = "journald"))]
+    journald_identifier: Option<String>,
+    flush_interval: Option<Duration>,
+    #[cfg(unix)]
+    unix_socket_path: Option<String>,
+    #[cfg(all(windows, feature = "windows-eventlog"))]
+    event_source: Option<String>,
+    /// 测试时用于替代标准输出与标准错误的写入端。
+    #[cfg(test)]
+    pub(crate) test_console: Option<(MemoryWriter, MemoryWriter)>,
+}
+
+impl CloggerBuilder {
+    /// 创建一个使用默认配置的构建器。
+    ///
+    /// 默认的日志级别为 `Debug`，且不会写入任何日志文件。
+    pub fn new() -> Self {
+        CloggerBuilder {
+            log_file_path: None,
+            log_file_handle: None,
+            level: LevelFilter::Debug,
+            plain: false,
+            startup_message: None,
+            startup_level: Level::Info,
+            app_tag: None,
+            non_blocking: false,
+            ordered: false,
+            dedupe_consecutive: false,
+            progress_redraw: None,
+            print_summary: false,
+            clock: None,
+            timestamp_mode: TimestampMode::Local,
+            theme: ColorTheme::default(),
+            sanitize_control: false,
+            filters: Vec::new(),
+            record_filters: Vec::new(),
+            message_transform: None,
+            stdout_level: LevelFilter::Trace,
+            stderr_level: None,
+            routes: [Sink::Stdout; 5],
+            file_level: LevelFilter::Trace,
+            file_options: FileOptions::default(),
+            file_ansi_stripper: Some(Arc::new(strip_ansi)),
+            target_files: Vec::new(),
+            level_files: Vec::new(),
+            sink_matrix: None,
+            sync_level: None,
+            target_width: None,
+            separator: None,
+            show_crate_version: false,
+            show_location: true,
+            console_location: None,
+            file_location: None,
+            show_target: true,
+            relative_location: false,
+            max_record_len: None,
+            global_fields: Vec::new(),
+            masked_fields: Vec::new(),
+            mask_case_sensitive: false,
+            console_capture_path: None,
+            #[cfg(feature = "binary")]
+            binary_path: None,
+            #[cfg(feature = "sqlite")]
+            sqlite_path: None,
+            #[cfg(feature = "sqlite")]
+            sqlite_flat_location: false,
+            #[cfg(all(target_os = "linux", feature = "journald"))]
+            journald_identifier: None,
+            flush_interval: None,
+            #[cfg(unix)]
+            unix_socket_path: None,
+            #[cfg(all(windows, feature = "windows-eventlog"))]
+            event_source: None,
+            #[cfg(test)]
+            test_console: None,
+        }
+    }
+
+    /// 设置日志文件的保存位置。
+    ///
+    /// 通过 [`CloggerBuilder::init`] 初始化时，若设置了非空的 `CLOGGER_FILE` 环境变量，将优先使用环境变量中的路径。
+    ///
+    /// # 参数
+    /// - `log_file_path`: 日志文件的保存位置。
+    pub fn log_file(mut self, log_file_path: &str) -> Self {
+        self.log_file_path = Some(log_file_path.to_string());
+        self
+    }
+
+    /// 将日志写入预先打开的文件，而不是按路径打开日志文件。
+    ///
+    /// 适合无法自行打开文件的环境，例如由 systemd 等进程管理器传入文件描述符，或受 seccomp 限制禁止调用 `open` 的沙箱。
+    /// 日志从文件末尾开始写入，格式、颜色处理与终端输出等其他配置不受影响。设置后 [`CloggerBuilder::log_file`]
+    /// 与 `CLOGGER_FILE` 环境变量不再生效；这类文件没有路径，因此 [`current_log_path`](crate::current_log_path)
+    /// 返回 `None`，收到 `SIGHUP` 时也无法重新打开，但仍可以通过 [`set_log_file`](crate::set_log_file) 切换到新的路径。
+    ///
+    /// 初始化时会复制一份文件句柄，复制失败（如文件描述符已耗尽）时会在标准错误中输出原因并跳过日志文件，
+    /// 终端等其他输出端不受影响。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use std::fs::OpenOptions;
+    ///
+    /// let file = OpenOptions::new()
+    ///     .create(true)
+    ///     .append(true)
+    ///     .open("/tmp/clogger_example.log")
+    ///     .unwrap();
+    /// CloggerBuilder::new().log_file_handle(file).init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `file`: 已打开的可写文件。
+    pub fn log_file_handle(mut self, file: File) -> Self {
+        self.log_file_handle = Some(file);
+        self
+    }
+
+    /// 设置日志级别。
+    ///
+    /// 若设置了 `CLOGGER_LEVEL` 或 `RUST_LOG` 环境变量且其中包含合法的日志级别（如 `info`、`trace`），将优先使用环境变量中的级别，
+    /// 两者同时设置时以 `CLOGGER_LEVEL` 为准（`CLOGGER_LEVEL` 无法解析时使用 `RUST_LOG`）。`RUST_LOG` 可以使用 `env_logger` 的语法，
+    /// 如 `warn,myapp=debug`，其中不带模块名称的项（`warn`）为全局级别；CLogger 不支持按模块设置级别，
+    /// `myapp=debug` 形式的项会被忽略。无法解析或被忽略的设置会在初始化后以警告的形式输出。
+    ///
+    /// # 参数
+    /// - `level`: 日志级别，可配合 [`level_from_verbosity`] 使用。
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// 设置日志需要额外写入的 Unix 域套接字（仅 Unix 平台可用）。
+    ///
+    /// 写入套接字的日志不包含颜色，且与终端、日志文件的输出互不影响。
+    /// 若连接断开（例如日志收集器重启），CLogger 会以指数退避的方式自动重连，等待重连期间的日志将被丢弃。
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .unix_socket("/run/logcollector.sock")
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `path`: 套接字的路径。
+    #[cfg(unix)]
+    pub fn unix_socket(mut self, path: &str) -> Self {
+        self.unix_socket_path = Some(path.to_string());
+        self
+    }
+
+    /// 设置日志需要额外写入的 Windows 事件日志（仅 Windows 平台可用，需要启用 `windows-eventlog` 特性）。
+    ///
+    /// 日志会以 `[模块名称] 日志内容` 的形式写入名为 `source` 的事件源，可以在事件查看器的“Windows 日志 > 应用程序”中查看。
+    /// `Error` 与 `Warn` 级别分别记录为错误与警告事件，其他级别记录为信息事件。终端与日志文件的输出不受影响。
+    ///
+    /// 事件源需要预先在注册表中注册（通常由安装程序在
+    /// `HKLM\SYSTEM\CurrentControlSet\Services\EventLog\Application\<source>` 下创建），
+    /// 否则事件查看器会提示找不到事件的描述，但日志内容仍会被完整记录。
+    ///
+    /// # 示例
+    /// ```rust,ignore
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("C:\\ProgramData\\MyService\\service.log")
+    ///     .windows_event_log("MyService")
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `source`: 事件源的名称。
+    #[cfg(all(windows, feature = "windows-eventlog"))]
+    pub fn windows_event_log(mut self, source: &str) -> Self {
+        self.event_source = Some(source.to_string());
+        self
+    }
+
+    /// 设置输出到终端（标准输出）的最低日志级别。
+    ///
+    /// 每个输出端都会独立地判断日志是否满足自己的级别，满足多个输出端级别的日志会分别写入每个输出端。
+    /// 输出端的级别只能进一步过滤日志，低于 [`CloggerBuilder::level`] 的日志不会写入任何输出端。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use log::{Level, LevelFilter};
+    ///
+    /// // Info 及以上输出到标准输出，Warn 及以上额外输出到标准错误，Debug 及以上写入日志文件
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .stdout_level(LevelFilter::Info)
+    ///     .stderr_level(LevelFilter::Warn)
+    ///     .file_level(LevelFilter::Debug)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `level`: 标准输出的最低日志级别。
+    pub fn stdout_level(mut self, level: LevelFilter) -> Self {
+        self.stdout_level = level;
+        self
+    }
+
+    /// 将指定级别及以上的日志额外输出到标准错误。
+    ///
+    /// 默认不会输出到标准错误。输出到标准错误的日志不影响标准输出，两者会各自写入。
+    ///
+    /// # 参数
+    /// - `level`: 标准错误的最低日志级别。
+    pub fn stderr_level(mut self, level: LevelFilter) -> Self {
+        self.stderr_level = Some(level);
+        self
+    }
+
+    /// 设置指定级别的日志在终端中输出到哪个流。
+    ///
+    /// 默认所有级别都输出到标准输出。被路由到标准错误的日志不会再输出到标准输出，且不受
+    /// [`CloggerBuilder::stdout_level`] 的限制；[`CloggerBuilder::stderr_level`] 仍会额外将满足级别的日志复制到标准错误，
+    /// 同一条日志最多只会在标准错误中出现一次。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{CloggerBuilder, Sink};
+    /// use log::Level;
+    ///
+    /// // 标准输出留给程序数据，调试与追踪日志输出到标准错误
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .route(Level::Debug, Sink::Stderr)
+    ///     .route(Level::Trace, Sink::Stderr)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `level`: 日志级别。
+    /// - `sink`: 该级别的日志在终端中的输出流。
+    pub fn route(mut self, level: Level, sink: Sink) -> Self {
+        self.routes[level as usize - 1] = sink;
+        self
+    }
+
+    /// 设置写入日志文件的最低日志级别。
+    ///
+    /// # 参数
+    /// - `level`: 日志文件的最低日志级别。
+    pub fn file_level(mut self, level: LevelFilter) -> Self {
+        self.file_level = level;
+        self
+    }
+
+    /// 将模块名称以 `prefix` 开头的日志额外写入单独的日志文件。
+    ///
+    /// 适合将审计日志等需要单独保存的日志与常规的运行日志分开。`exclusive` 为 `true` 时，匹配的日志只会写入该文件，
+    /// 不再写入 [`CloggerBuilder::log_file`] 设置的日志文件；终端的输出不受影响。
+    /// 多次调用可以设置多个路由，同一条日志会写入所有匹配的文件。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{c_log, CloggerBuilder};
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example_app.log")
+    ///     .target_file("audit::", "/tmp/clogger_example_audit.log", true)
+    ///     .init();
+    /// c_log!("audit::login", "用户已登录"); // 只写入 audit.log
+    /// c_log!("app::server", "服务已启动"); // 只写入 app.log
+    /// ```
+    ///
+    /// # 参数
+    /// - `prefix`: 模块名称的前缀。
+    /// - `path`: 日志文件的保存位置。
+    /// - `exclusive`: 匹配的日志是否不再写入默认的日志文件。
+    pub fn target_file(mut self, prefix: &str, path: &str, exclusive: bool) -> Self {
+        self.target_files.push(TargetFile {
+            prefix: prefix.to_string(),
+            path: path.to_string(),
+            exclusive,
+        });
+        self
+    }
+
+    /// 将指定级别的日志额外写入一个单独的日志文件。
+    ///
+    /// 该文件只包含级别恰好为 `level` 的日志（而不是 `level` 及以上），适合需要 `info.log`、`warn.log`、`error.log`
+    /// 等按级别分开保存日志的监控工具。文件使用与 [`CloggerBuilder::log_file`] 相同的格式与文件选项，
+    /// 同样受 [`CloggerBuilder::file_level`] 的限制；[`CloggerBuilder::log_file`] 设置的日志文件与终端的输出不受影响，
+    /// 只需要按级别分开的文件时可以不设置默认的日志文件。多次调用可以为各个级别分别设置文件，
+    /// 同一级别设置了多个文件时会写入所有的文件。无法打开的文件会在标准错误中说明原因后被跳过，不影响其他输出端。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{c_error, c_log, c_warn, CloggerBuilder};
+    /// use log::Level;
+    ///
+    /// CloggerBuilder::new()
+    ///     .level_file(Level::Info, "/tmp/clogger_example_info.log")
+    ///     .level_file(Level::Warn, "/tmp/clogger_example_warn.log")
+    ///     .level_file(Level::Error, "/tmp/clogger_example_error.log")
+    ///     .init();
+    /// c_log!("服务已启动"); // 只写入 info.log
+    /// c_warn!("磁盘空间不足"); // 只写入 warn.log
+    /// c_error!("无法连接到数据库"); // 只写入 error.log
+    /// ```
+    ///
+    /// # 参数
+    /// - `level`: 写入该文件的日志级别。
+    /// - `path`: 日志文件的保存位置。
+    pub fn level_file(mut self, level: Level, path: &str) -> Self {
+        self.level_files.push((level, path.to_string()));
+        self
+    }
+
+    /// 设置按日志级别路由输出目标的路由表。
+    ///
+    /// 路由表中的每条路由会将指定级别的日志写入一个输出目标（标准输出、标准错误、日志文件或自定义的写入端），
+    /// 并可以为该目标单独设置日志格式，详见 [`SinkMatrix`](crate::SinkMatrix)。路由表中的目标是额外添加的输出端，
+    /// 默认的终端输出与 [`CloggerBuilder::log_file`] 等设置仍然有效；需要完全由路由表决定终端输出时，
+    /// 可以通过 `stdout_level(LevelFilter::Off)` 关闭默认的标准输出。路由表中的目标不受 [`CloggerBuilder::stdout_level`] 等各输出端级别的限制，
+    /// 但仍受全局日志级别与过滤函数的限制。多次调用时，后设置的路由表会覆盖之前的设置。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{CloggerBuilder, Destination, SinkMatrix};
+    /// use log::Level;
+    ///
+    /// // 在默认输出之外，将警告与错误单独写入一个文件
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .sink_matrix(SinkMatrix::new().route(
+    ///         &[Level::Error, Level::Warn],
+    ///         Destination::file("/tmp/clogger_example_problems.log"),
+    ///     ))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `matrix`: 路由表。
+    pub fn sink_matrix(mut self, matrix: SinkMatrix) -> Self {
+        self.sink_matrix = Some(matrix);
+        self
+    }
+
+    /// 设置终端输出的副本文件。
+    ///
+    /// 设置后，输出到标准输出的内容（包括颜色等 ANSI 转义序列）会原样写入该文件，便于之后在终端中回放（如 `cat console.ansi`）。
+    /// 常规的日志文件仍然不包含颜色，两者互不影响。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .console_capture("/tmp/clogger_example_console.ansi")
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `path`: 副本文件的保存位置。
+    pub fn console_capture(mut self, path: &str) -> Self {
+        self.console_capture_path = Some(path.to_string());
+        self
+    }
+
+    /// 设置需要额外以紧凑的二进制格式写入的日志文件（需要启用 `binary` 特性）。
+    ///
+    /// 每条日志只记录时间戳、级别、模块名称与日志信息内容，写入时不进行文本格式化，
+    /// 适合需要高吞吐量的场景，文件也比文本格式小得多。写入的文件可以通过 [`decode_binary_log`](crate::decode_binary_log) 解码。
+    /// 与日志文件相同，该文件遵循 [`CloggerBuilder::file_level`] 与 [`CloggerBuilder::buffered`] 等设置，但不支持回绕写入。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .binary_file("/tmp/clogger_example.bin")
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `path`: 二进制日志文件的保存位置。
+    #[cfg(feature = "binary")]
+    pub fn binary_file(mut self, path: &str) -> Self {
+        self.binary_path = Some(path.to_string());
+        self
+    }
+
+    /// 设置需要额外写入的 SQLite 数据库（需要启用 `sqlite` 特性）。
+    ///
+    /// 每条日志会作为一行写入数据库的 `logs` 表（不存在时自动创建），列包括 `ts`、`level`、`target`、`file`、`line`、
+    /// `message` 以及以 JSON 对象保存的键值对 `fields`。日志由后台线程批量写入，积压的日志会合并到同一个事务中。
+    /// `fields` 中还会包含调用位置，默认写为嵌套对象，可以通过 [`sqlite_flat_location`](Self::sqlite_flat_location) 修改。
+    /// 写入数据库的日志与日志文件使用相同的级别（[`file_level`](Self::file_level) 或 [`set_file_level`](crate::set_file_level)），
+    /// [`flush_clogger`](crate::flush_clogger) 会等待积压的日志提交到数据库。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .sqlite("/tmp/clogger_example.db")
+    ///     .init();
+    /// ```
+    ///
+    /// 之后便可以使用 SQL 查询日志，例如 `SELECT * FROM logs WHERE level = 'ERROR' AND target LIKE 'app::net%'`。
+    ///
+    /// # 参数
+    /// - `path`: 数据库文件的保存位置。
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite(mut self, path: &str) -> Self {
+        self.sqlite_path = Some(path.to_string());
+        self
+    }
+
+    /// 设置 SQLite 数据库的 `fields` 列中调用位置的写法（需要启用 `sqlite` 特性）。
+    ///
+    /// 默认写为嵌套对象 `{"location":{"file":"src/main.rs","line":42,"column":7}}`，
+    /// 设置为 `true` 后改为扁平的 `{"file":"src/main.rs","line":42,"column":7}`，便于只支持单层键的日志收集系统处理。
+    /// 位置信息从 CLogger 宏生成的日志目标中提取，Windows 路径中盘符后的冒号不会影响解析；
+    /// 其他来源的日志使用记录中的文件与行号，此时不包含 `column`。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .sqlite("/tmp/clogger_example.db")
+    ///     .sqlite_flat_location(true)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `flat`: 是否将调用位置写为扁平的键。
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite_flat_location(mut self, flat: bool) -> Self {
+        self.sqlite_flat_location = flat;
+        self
+    }
+
+    /// 设置日志需要额外发送到的 systemd 日志（journald，仅 Linux 平台可用，需要启用 `journald` 特性）。
+    ///
+    /// 日志通过 journald 的原生协议发送，日志级别会转换为对应的优先级（`Trace` 与 `Debug` 均为 `debug`），
+    /// 模块名称与调用位置分别作为 `TARGET`、`CODE_FILE`、`CODE_LINE` 与 `CODE_COLUMN` 字段，
+    /// 键值对会转换为大写的同名字段（如 `request_id` 对应 `REQUEST_ID`），便于使用 `journalctl` 按字段查询；
+    /// 与上述字段同名的键值对会加上 `FIELD_` 前缀（如 `message` 对应 `FIELD_MESSAGE`），不会覆盖这些字段。
+    /// 终端与日志文件的输出不受影响，因此本地运行时仍可以在终端中查看日志。
+    ///
+    /// 无法连接到 journald（如未使用 systemd 的系统或容器中）时，CLogger 会在标准错误中输出一条警告并跳过该输出端。
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .journald("my-service")
+    ///     .init();
+    /// ```
+    ///
+    /// 之后便可以使用 `journalctl -t my-service -p warning` 或 `journalctl TARGET=app::net` 查询日志。
+    ///
+    /// # 参数
+    /// - `identifier`: 日志的 `SYSLOG_IDENTIFIER`，通常为程序或服务的名称。
+    #[cfg(all(target_os = "linux", feature = "journald"))]
+    pub fn journald(mut self, identifier: &str) -> Self {
+        self.journald_identifier = Some(identifier.to_string());
+        self
+    }
+
+    /// 设置是否缓冲写入日志文件。
+    ///
+    /// 默认情况下每条日志都会立即写入磁盘。启用缓冲后，日志会先保存在内存缓冲区中，
+    /// 缓冲区已满时才会写入磁盘，以减少系统调用、提高吞吐量。缓冲区中的日志可能在程序崩溃时丢失，
+    /// 可以通过 [`CloggerBuilder::flush_interval`] 定期刷新，或调用 [`flush_clogger`](crate::flush_clogger) 手动刷新。
+    ///
+    /// # 参数
+    /// - `buffered`: 是否缓冲写入。
+    pub fn buffered(mut self, buffered: bool) -> Self {
+        self.file_options.buffered = buffered;
+        self
+    }
+
+    /// 设置是否以交互模式写入日志文件。
+    ///
+    /// 启用后每条日志写入后都会立即刷新到文件，即使启用了 [`CloggerBuilder::buffered`]，
+    /// 使用 `tail -f` 实时查看日志时每一行都会及时出现，而不是成批出现。适合在调试时临时开启。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 生产配置启用了缓冲，调试时强制逐行刷新
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .buffered(true)
+    ///     .interactive_file(true)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `interactive`: 是否逐行刷新。
+    pub fn interactive_file(mut self, interactive: bool) -> Self {
+        self.file_options.interactive = interactive;
+        self
+    }
+
+    /// 设置写入日志文件前用于移除 ANSI 转义序列的函数。
+    ///
+    /// 日志文件中的 CLogger 输出本身不带颜色，但日志内容（如来自外部命令的输出或用户输入）中仍可能带有转义序列，
+    /// 默认使用 [`strip_ansi`](crate::strip_ansi) 将其从日志内容与键值对中移除后再写入日志文件（包括 [`CloggerBuilder::target_file`] 添加的文件）。
+    /// 传入 `None` 时将原样写入转义序列。终端等其他输出端不受影响。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use std::borrow::Cow;
+    /// use std::sync::Arc;
+    ///
+    /// // 保留日志内容中的转义序列，只移除响铃字符
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .file_ansi_stripper(Some(Arc::new(|text: &str| {
+    ///         if text.contains('\x07') {
+    ///             Cow::Owned(text.replace('\x07', ""))
+    ///         } else {
+    ///             Cow::Borrowed(text)
+    ///         }
+    ///     })))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `stripper`: 移除转义序列的函数，`None` 表示不做处理。
+    pub fn file_ansi_stripper(mut self, stripper: Option<AnsiStripper>) -> Self {
+        self.file_ansi_stripper = stripper;
+        self
+    }
+
+    /// 设置是否移除写入日志文件的日志内容中的 ANSI 转义序列（默认启用）。
+    ///
+    /// 启用时使用 [`strip_ansi`](crate::strip_ansi)，需要自定义处理方式时请使用 [`CloggerBuilder::file_ansi_stripper`]。
+    ///
+    /// # 参数
+    /// - `strip`: 是否移除转义序列。
+    pub fn strip_file_ansi(self, strip: bool) -> Self {
+        self.file_ansi_stripper(strip.then(|| Arc::new(strip_ansi) as AnsiStripper))
+    }
+
+    /// 设置日志文件的定期刷新间隔。
+    ///
+    /// 设置后 CLogger 会为每个日志文件（包括 [`CloggerBuilder::target_file`] 等路由的文件）启动一个后台线程，
+    /// 每隔 `interval` 将缓冲区中的日志写入磁盘，程序崩溃时最多丢失一个间隔内的日志。
+    /// 该选项仅在启用 [`CloggerBuilder::buffered`] 时有意义。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use std::time::Duration;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .buffered(true)
+    ///     .flush_interval(Duration::from_secs(1))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `interval`: 刷新间隔。
+    pub fn flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = Some(interval);
+        self
+    }
+
+    /// 将日志文件限制在固定的大小内循环写入。
+    ///
+    /// 启用后，日志文件的开头会保留一行 `CLOGGER-WRAP <偏移量>` 形式的头部标记，记录下一条日志的写入位置。
+    /// 写入的日志即将超过 `max_size` 字节时，CLogger 会回到头部标记之后，从文件开头开始覆盖最旧的日志，
+    /// 使磁盘占用始终不超过上限，适合存储空间有限的嵌入式设备。
+    ///
+    /// 阅读日志时，最旧的日志从头部标记记录的偏移量开始，读到文件末尾后再从头部标记之后读到该偏移量。
+    /// 偏移量之后的第一行可能是被部分覆盖的旧日志。单条日志超过上限时仍会被完整写入，文件会略微超出上限。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 日志文件最多占用 64 KiB
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example_wrap.log")
+    ///     .max_file_size_wrap(64 * 1024)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `max_size`: 日志文件的大小上限（字节）。
+    pub fn max_file_size_wrap(mut self, max_size: u64) -> Self {
+        self.file_options.wrap_size = Some(max_size);
+        self
+    }
+
+    /// 设置是否在新建的日志文件开头写入 UTF-8 BOM。
+    ///
+    /// 部分 Windows 上的旧工具（如记事本的旧版本）会将没有 BOM 的 UTF-8 文件误认为本地编码，导致中文与表情符号显示为乱码。
+    /// 启用后只会在日志文件为空（新建或被截断）时写入 BOM，追加到已有内容的文件时不会写入。
+    /// 该设置对按级别路由的文件与终端输出的副本文件同样生效；回绕模式下不会写入 BOM。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example_bom.log")
+    ///     .utf8_bom(true)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `bom`: 是否写入 UTF-8 BOM。
+    pub fn utf8_bom(mut self, bom: bool) -> Self {
+        self.file_options.utf8_bom = bom;
+        self
+    }
+
+    /// 设置需要立即同步到存储设备的最低日志级别。
+    ///
+    /// 达到该级别的日志写入日志文件后，CLogger 会立即刷新缓冲区并调用 [`File::sync_data`](std::fs::File::sync_data)，
+    /// 确保关键的日志（以及在它之前缓冲的日志）在断电等情况下也不会丢失。低于该级别的日志仍按照 [`CloggerBuilder::buffered`] 的设置写入。
+    /// 同步操作的开销较大，通常只应在 `Error` 等较少出现的级别上启用。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use log::Level;
+    ///
+    /// // 常规日志缓冲写入，错误日志立即同步到磁盘
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .buffered(true)
+    ///     .sync_level(Level::Error)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `level`: 需要立即同步的最低日志级别。
+    pub fn sync_level(mut self, level: Level) -> Self {
+        self.sync_level = Some(level);
+        self
+    }
+
+    /// 设置模块名称及位置信息所占的列宽。
+    ///
+    /// 设置后，较短的模块名称会以空格补齐，超出的部分会被截断并以 `…` 结尾，使各行的日志内容对齐。
+    /// 列宽按照字符的显示宽度计算，中日韩文字等宽字符按两列计算。
+    ///
+    /// # 参数
+    /// - `width`: 列宽。
+    pub fn target_width(mut self, width: usize) -> Self {
+        self.target_width = Some(width);
+        self
+    }
+
+    /// 设置日志中各字段之间的分隔符。
+    ///
+    /// 默认各字段以空格分隔，并分别使用括号包裹（如 `(时间戳) [I] [模块名称] 内容`）。设置分隔符后，
+    /// 时间戳、标签、级别标记、模块名称与日志内容将直接以分隔符连接，且不再补齐列宽，便于下游工具按分隔符拆分。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 输出形如 `2024-06-01 12:00:00.000|I|app::server|服务已启动` 的日志
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .separator("|")
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `separator`: 分隔符。
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = Some(separator.to_string());
+        self
+    }
+
+    /// 设置是否在模块名称后显示调用处所在 crate 的版本。
+    ///
+    /// 启用后，通过 CLogger 全局宏（如 [`c_log!`](crate::c_log)）输出的日志会以 `[模块名称@版本 (位置)]` 的形式显示
+    /// 调用处所在 crate 的 `CARGO_PKG_VERSION`，便于在包含多个 crate（或同一 crate 的多个版本）的工作区中定位日志来源。
+    /// 直接使用 `log` 宏或 [`Clogger`] 实例方法输出的日志不包含版本信息。
+    ///
+    /// # 参数
+    /// - `show`: 是否显示 crate 的版本。
+    pub fn show_crate_version(mut self, show: bool) -> Self {
+        self.show_crate_version = show;
+        self
+    }
+
+    /// 设置是否在模块名称后显示调用位置（`(文件:行号^列号)`），默认显示。
+    ///
+    /// # 参数
+    /// - `show`: 是否显示调用位置。
+    pub fn show_location(mut self, show: bool) -> Self {
+        self.show_location = show;
+        self
+    }
+
+    /// 设置是否将调用位置中的文件路径显示为相对于调用处 crate 根目录（`CARGO_MANIFEST_DIR`）的路径，默认不启用。
+    ///
+    /// 部分构建环境中调用位置的文件路径是绝对路径（如 `/home/dev/app/src/main.rs`），会在日志中暴露构建机器的目录结构。
+    /// 启用后这类路径会显示为 `src/main.rs`，使不同机器上构建的程序输出一致的位置信息；
+    /// 不在 crate 根目录中的路径保持原样。仅对通过 CLogger 全局宏输出的日志生效。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .relative_location(true)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `relative`: 是否显示相对路径。
+    pub fn relative_location(mut self, relative: bool) -> Self {
+        self.relative_location = relative;
+        self
+    }
+
+    /// 单独设置终端（标准输出与标准错误）中是否显示调用位置，未设置时与 [`CloggerBuilder::show_location`] 相同。
+    ///
+    /// # 参数
+    /// - `show`: 是否在终端中显示调用位置。
+    pub fn show_location_stdout(mut self, show: bool) -> Self {
+        self.console_location = Some(show);
+        self
+    }
+
+    /// 单独设置日志文件中是否显示调用位置，未设置时与 [`CloggerBuilder::show_location`] 相同。
+    ///
+    /// 适合在终端中保留便于跳转的 `文件:行号`，同时减小归档的日志文件的体积。
+    /// 对主日志文件与 [`CloggerBuilder::target_file`] 路由的日志文件生效，路由表中的输出端使用其自身的格式设置。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 终端中显示调用位置，日志文件中不显示
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .show_location_file(false)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `show`: 是否在日志文件中显示调用位置。
+    pub fn show_location_file(mut self, show: bool) -> Self {
+        self.file_location = Some(show);
+        self
+    }
+
+    /// 设置是否显示日志目标（模块名称与调用位置），默认显示。
+    ///
+    /// 不显示时日志形如 `(时间) [I] 日志内容`，适合不关心日志来源的简单命令行工具。
+    /// 此时 CLogger 宏不会再为每条日志构建包含位置信息的目标字符串，按模块名称的过滤与路由仍然有效。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .show_target(false)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `show`: 是否显示日志目标。
+    pub fn show_target(mut self, show: bool) -> Self {
+        self.show_target = show;
+        self
+    }
+
+    /// 设置单条日志内容的长度上限（字节），默认为 1 MiB。
+    ///
+    /// 这是防止意外记录超大内容（如误将整个文件内容作为日志输出）耗尽内存的安全措施：超出上限的部分会在格式化时被直接丢弃，
+    /// 不会先构建完整的字符串，被截断的日志以 ` ...[truncated]` 结尾，且第一次截断时会在标准错误中输出一条警告。
+    /// 该上限应用于终端、日志文件等所有文本输出端，以及二进制日志、SQLite 数据库、systemd 日志、
+    /// [`message_transform`](Self::message_transform) 与 [`dedupe_consecutive`](Self::dedupe_consecutive)。
+    /// 初始化之前由 [`buffer_early_logs`](crate::buffer_early_logs) 缓冲的日志使用默认的上限。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 单条日志最多保留 64 KiB 的内容
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .max_record_len(64 * 1024)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `max_len`: 单条日志内容的长度上限（字节）。
+    pub fn max_record_len(mut self, max_len: usize) -> Self {
+        self.max_record_len = Some(max_len);
+        self
+    }
+
+    /// 设置附加在每条日志末尾的全局键值对，如主机名、服务名、部署区域等。
+    ///
+    /// 全局键值对以 ` 键=值` 的形式追加在日志记录自身的键值对之后，对终端、日志文件等所有文本输出端生效，
+    /// 转义规则与日志记录中的键值对相同。多次调用时以最后一次设置为准。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 每条日志都会以 ` service=api region=cn-east` 结尾
+    /// CloggerBuilder::new()
+    ///     .global_fields(&[("service", "api"), ("region", "cn-east")])
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `fields`: 全局键值对，按给定顺序输出。
+    pub fn global_fields(mut self, fields: &[(&str, &str)]) -> Self {
+        self.global_fields = fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        self
+    }
+
+    /// 设置需要隐藏值的键值对名称，如 `password`、`token` 等。
+    ///
+    /// 名称匹配的键值对（包括日志记录中的键值对与 [`CloggerBuilder::global_fields`]）的值会被替换为 `***`，
+    /// 对终端、日志文件等文本输出端以及 SQLite 数据库的 `fields` 列均生效。与按内容匹配不同，
+    /// 无论值是什么都会被隐藏。默认匹配时不区分大小写，可以通过 [`CloggerBuilder::mask_fields_case_sensitive`] 修改。
+    /// 多次调用时以最后一次设置为准。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // `password=...` 与 `Token=...` 都会输出为 `***`
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .mask_fields(&["password", "token"])
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `names`: 需要隐藏值的键值对名称。
+    pub fn mask_fields(mut self, names: &[&str]) -> Self {
+        self.masked_fields = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// 设置 [`CloggerBuilder::mask_fields`] 匹配名称时是否区分大小写，默认不区分。
+    ///
+    /// # 参数
+    /// - `case_sensitive`: 是否区分大小写。
+    pub fn mask_fields_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.mask_case_sensitive = case_sensitive;
+        self
+    }
+
+    /// 适合窄终端（如 80 列的终端或分屏）的紧凑格式预设。
+    ///
+    /// 时间戳缩短为 `%H:%M:%S`，不显示调用位置，并将模块名称截断为 16 列，为日志内容留出尽可能多的宽度。
+    /// 等价于依次调用 [`CloggerBuilder::timestamp_mode`]、[`CloggerBuilder::show_location`] 与
+    /// [`CloggerBuilder::target_width`]，之后的调用可以单独覆盖其中的设置。该格式会应用到所有文本输出端（包括日志文件）。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 输出形如 `(12:00:00) [I] [app::server]      服务已启动` 的日志
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .compact()
+    ///     .init();
+    /// ```
+    pub fn compact(self) -> Self {
+        self.timestamp_mode(TimestampMode::Custom("%H:%M:%S".to_string()))
+            .show_location(false)
+            .target_width(16)
+    }
+
+    /// 兼顾写入性能与关键日志可靠性的日志文件预设。
+    ///
+    /// 启用缓冲写入，将 `Warn` 设为立即同步的级别，并每隔 1 秒刷新一次缓冲区，等价于依次调用
+    /// [`CloggerBuilder::buffered`]、[`CloggerBuilder::sync_level`] 与 [`CloggerBuilder::flush_interval`]，
+    /// 之后的调用可以单独覆盖其中的设置。该预设提供以下保证：
+    ///
+    /// - `Warn` 与 `Error` 日志写入的调用返回时，该日志以及在它之前缓冲的所有日志都已写入日志文件并通过
+    ///   [`File::sync_data`](std::fs::File::sync_data) 同步到存储设备，即使随后断电也不会丢失；
+    /// - 其他级别的日志最迟约 1 秒后写入日志文件（不同步到存储设备），程序崩溃时最多丢失这段时间内的日志，
+    ///   断电时则可能丢失更多尚未被操作系统写入磁盘的日志；
+    /// - 通过 [`shutdown`](crate::shutdown) 或 [`flush_clogger`](crate::flush_clogger) 正常退出时不会丢失日志。
+    ///
+    /// 第一条保证适用于 [`CloggerBuilder::log_file`]、[`CloggerBuilder::target_file`] 与 [`CloggerBuilder::level_file`] 设置的文件；
+    /// 后两条保证适用于 CLogger 打开的所有日志文件，包括 [`CloggerBuilder::console_capture`] 与路由表中的文件。
+    /// 同时启用 [`CloggerBuilder::non_blocking`] 时日志由后台线程写入，调用返回时日志可能尚未写入，上述第一条保证不再成立。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .reliable()
+    ///     .init();
+    /// ```
+    pub fn reliable(self) -> Self {
+        self.buffered(true)
+            .sync_level(Level::Warn)
+            .flush_interval(Duration::from_secs(1))
+    }
+
+    /// 设置是否启用纯文本模式。
+    ///
+    /// 启用后 CLogger 不会输出任何颜色，且由 CLogger 自身生成的文本（如初始化提示）只包含 ASCII 字符，
+    /// 便于 CI 等自动化工具解析日志。用户传入的日志内容不受影响。
+    ///
+    /// 未启用纯文本模式时，CLogger 按照以下顺序决定终端输出是否着色：
+    /// 1. 设置了 `CLICOLOR_FORCE` 且其值不为 `0` 时始终着色；
+    /// 2. 设置了 `NO_COLOR` 时不着色；
+    /// 3. `TERM` 为 `dumb`（或在 Unix 平台上未设置 `TERM`）时不着色；
+    /// 4. `CLICOLOR` 为 `0` 时不着色；
+    /// 5. 对应的输出流（标准输出或标准错误）不是终端（如被重定向到文件或管道）时不着色；
+    /// 6. 其他情况下着色。
+    ///
+    /// 标准输出与标准错误分别判断，且不受 `colored::control` 全局设置的影响。
+    /// 初始化后可以通过 [`set_colors`](crate::set_colors) 在运行时覆盖以上判断。
+    ///
+    /// # 参数
+    /// - `plain`: 是否启用纯文本模式。
+    pub fn plain(mut self, plain: bool) -> Self {
+        self.plain = plain;
+        self
+    }
+
+    /// 设置附加在每条日志中的标签。
+    ///
+    /// 标签会以 `[标签]` 的形式出现在时间戳之后，适合用于标记应用的版本号或 Git 提交，
+    /// 便于区分来自多个部署版本的混合日志。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .app_tag(concat!("v", env!("CARGO_PKG_VERSION")))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `tag`: 标签内容。
+    pub fn app_tag(mut self, tag: &str) -> Self {
+        self.app_tag = Some(tag.to_string());
+        self
+    }
+
+    /// 设置是否启用非阻塞写入。
+    ///
+    /// 启用后，格式化完成的日志会通过通道发送给专用的后台线程，由后台线程完成写入终端、日志文件等阻塞的 I/O 操作，
+    /// 记录日志的线程永远不会因 I/O 而阻塞。适合在 Tokio 等异步运行时中使用，避免阻塞运行时的工作线程。
+    ///
+    /// 通道没有容量限制，若日志的产生速度长期高于写入速度，尚未写入的日志会占用越来越多的内存。
+    /// [`flush_clogger`](crate::flush_clogger)（以及 [`c_assert!`](crate::c_assert) 等在 panic 之前刷新的宏）
+    /// 会等待后台线程写入积压的日志后再返回。
+    ///
+    /// # 参数
+    /// - `non_blocking`: 是否启用非阻塞写入。
+    pub fn non_blocking(mut self, non_blocking: bool) -> Self {
+        self.non_blocking = non_blocking;
+        self
+    }
+
+    /// 设置是否保证所有输出端中日志的顺序一致。
+    ///
+    /// 默认情况下，每个输出端（终端、日志文件等）各自持有写入锁，多个线程同时记录日志时，
+    /// 同一线程中的日志在每个输出端中都保持记录的顺序，但不同线程的日志在不同输出端中的先后顺序可能不同。
+    ///
+    /// 启用后，每条日志都会在同一个全局锁内依次写入所有输出端，所有输出端中的日志顺序完全一致，便于对照终端与日志文件。
+    /// 与 [`CloggerBuilder::non_blocking`] 同时启用时，日志也会按照相同的顺序交给各个后台线程。
+    /// 代价是多个线程无法再同时写入不同的输出端，高并发时吞吐量会有所下降。
+    ///
+    /// # 参数
+    /// - `ordered`: 是否保证顺序一致。
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// 设置与 `\r` 进度条协调输出时，重新绘制进度条的回调函数。
+    ///
+    /// 程序的其他部分使用 `\r` 在标准输出中刷新进度条时，日志会与进度条混在同一行中。设置该回调后，
+    /// 标准输出连接到终端时，每条输出到标准输出的日志前都会加上 `\r\x1b[K` 清除当前行（即进度条），
+    /// 日志输出后再调用 `redraw` 重新绘制进度条，使日志始终显示在进度条的上方。标准输出被重定向时不受影响。
+    ///
+    /// 回调函数在写入日志的线程中调用（启用 [`CloggerBuilder::non_blocking`] 时为后台线程），
+    /// 其中不应再输出日志。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use std::io::Write;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let progress = Arc::new(AtomicUsize::new(0));
+    /// let current = progress.clone();
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .progress_redraw(move || {
+    ///         print!("\r进度: {}%", current.load(Ordering::Relaxed));
+    ///         let _ = std::io::stdout().flush();
+    ///     })
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `redraw`: 重新绘制进度条的回调函数。
+    pub fn progress_redraw(mut self, redraw: impl Fn() + Send + Sync + 'static) -> Self {
+        self.progress_redraw = Some(Arc::new(redraw));
+        self
+    }
+
+    /// 设置是否在 [`shutdown`](crate::shutdown) 时将各级别的日志条数输出到标准错误，默认不输出。
+    ///
+    /// 输出形如 `日志统计: 1203 条常规，12 条警告，3 条错误`（[`CloggerBuilder::plain`] 模式下为
+    /// `Logged: 1203 info, 12 warn, 3 error`），标准错误支持着色时，数量不为零的级别会按配色主题着色。
+    /// 统计的规则与 [`log_count`](crate::log_count) 相同。适合命令行工具在运行结束时直观地提示运行中是否出现了问题。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{c_warn, CloggerBuilder};
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .print_summary_on_shutdown(true)
+    ///     .init();
+    /// c_warn!("example::moudle_name", "跳过了一个无法读取的文件");
+    /// clogger::shutdown(); // 输出日志统计
+    /// ```
+    ///
+    /// # 参数
+    /// - `print`: 是否在关闭时输出日志统计。
+    pub fn print_summary_on_shutdown(mut self, print: bool) -> Self {
+        self.print_summary = print;
+        self
+    }
+
+    /// 设置是否合并连续重复的日志，默认不合并。
+    ///
+    /// 启用后，与上一条日志的级别、模块名称（包括调用位置）、日志内容及键值对均相同的日志（不比较时间戳）不会再次输出，
+    /// 而是在出现一条不同的日志时，以 `（重复了 N 次）`（[`CloggerBuilder::plain`] 模式下为 `(repeated N times)`）的形式输出重复的次数，类似于 syslog 的 `last message repeated N times`。
+    /// 适合减少紧密循环中反复出现的错误带来的噪音，同时保留重复的次数。
+    ///
+    /// 调用 [`flush_clogger`](crate::flush_clogger) 或 [`shutdown`](crate::shutdown) 时会立即输出尚未输出的重复次数，
+    /// 因此程序退出前调用 [`shutdown`](crate::shutdown) 即可避免丢失最后的重复次数。刷新后重新开始比较，
+    /// 之后相同的日志会再次输出。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{c_error, CloggerBuilder};
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .dedupe_consecutive(true)
+    ///     .init();
+    /// for _ in 0..100 {
+    ///     c_error!("example::moudle_name", "连接被拒绝"); // 只输出一次
+    /// }
+    /// clogger::shutdown(); // 输出 `（重复了 99 次）`
+    /// ```
+    ///
+    /// # 参数
+    /// - `dedupe`: 是否合并连续重复的日志。
+    pub fn dedupe_consecutive(mut self, dedupe: bool) -> Self {
+        self.dedupe_consecutive = dedupe;
+        self
+    }
+
+    /// 设置用于生成时间戳的时钟。
+    ///
+    /// 默认使用系统的本地时间。注入固定的时钟后，每条日志的时间戳都是确定的，便于对日志的输出内容进行快照测试。
+    /// 时钟会在每条日志格式化时被调用，因此也可以返回由模拟框架推进的虚拟时间，使日志的时间戳与模拟时间保持一致。
+    /// 时钟会应用到终端、日志文件与 SQLite 数据库等所有输出端；通过 [`c_log_at_time!`](crate::c_log_at_time)
+    /// 指定了时间的日志不受影响。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use chrono::{Duration, Local, TimeZone};
+    /// use clogger::CloggerBuilder;
+    /// use std::sync::atomic::{AtomicI64, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// // 模拟时间，单位为秒
+    /// let simulated = Arc::new(AtomicI64::new(0));
+    /// let start = Local.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+    /// let time = simulated.clone();
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .clock(move || start + Duration::seconds(time.load(Ordering::Relaxed)))
+    ///     .init();
+    ///
+    /// simulated.fetch_add(3600, Ordering::Relaxed); // 推进一小时
+    /// ```
+    ///
+    /// # 参数
+    /// - `clock`: 返回当前时间的函数。
+    pub fn clock(mut self, clock: impl Fn() -> DateTime<Local> + Send + Sync + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// 设置时间戳的格式，默认为 [`TimestampMode::Local`]。
+    ///
+    /// 该设置会应用到终端、日志文件等所有文本输出端，示例请参考 [`TimestampMode`]。
+    ///
+    /// # 参数
+    /// - `mode`: 时间戳的格式。
+    pub fn timestamp_mode(mut self, mode: TimestampMode) -> Self {
+        self.timestamp_mode = mode;
+        self
+    }
+
+    /// 设置终端输出时使用的配色主题。
+    ///
+    /// 主题只会应用在终端的输出中，写入日志文件的内容始终不带颜色。示例请参考 [`ColorTheme`]。
+    ///
+    /// # 参数
+    /// - `theme`: 配色主题。
+    pub fn theme(mut self, theme: ColorTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// 设置是否转义日志信息中的控制字符。
+    ///
+    /// 启用后，日志信息中的控制字符（如 `\r`、`\x1b`，换行符与制表符除外）会被转义为 `\xNN` 的形式后再写入所有输出端，
+    /// 防止不可信的输入破坏终端显示或伪造日志行（日志注入）。
+    ///
+    /// # 参数
+    /// - `sanitize`: 是否转义控制字符。
+    pub fn sanitize_control(mut self, sanitize: bool) -> Self {
+        self.sanitize_control = sanitize;
+        self
+    }
+
+    /// 添加一个根据日志元数据（级别与目标）过滤日志的函数，返回 `false` 的日志将被丢弃。
+    ///
+    /// 过滤函数在所有输出端之前执行，多次调用时日志需要通过所有的过滤函数。
+    /// 元数据中不包含日志内容，需要按内容过滤时请使用 [`CloggerBuilder::filter_record`]。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use std::sync::Arc;
+    ///
+    /// // 丢弃来自 hyper 的所有日志
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .filter(Arc::new(|metadata| !metadata.target().starts_with("hyper")))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `filter`: 过滤函数。
+    pub fn filter(mut self, filter: Arc<dyn Fn(&log::Metadata) -> bool + Send + Sync>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// 只保留模块名称以 `prefixes` 中任意一个前缀开头的日志，丢弃其他所有日志（如来自依赖库的日志）。
+    ///
+    /// 只与模块名称匹配，不包括 CLogger 附加在目标中的位置信息。等价于添加一个对应的 [`CloggerBuilder::filter`]，
+    /// 多次调用时日志需要同时满足每一次调用的前缀列表。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// // 只输出本项目的日志
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .only_targets(&["mycrate", "myother"])
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `prefixes`: 允许的模块名称前缀。
+    pub fn only_targets(self, prefixes: &[&str]) -> Self {
+        let prefixes: Vec<String> = prefixes.iter().map(|prefix| prefix.to_string()).collect();
+        self.filter(Arc::new(move |metadata| {
+            let (module, _) = split_target(metadata.target());
+            prefixes
+                .iter()
+                .any(|prefix| module.starts_with(prefix.as_str()))
+        }))
+    }
+
+    /// 添加一个根据完整的日志记录（包括日志内容）过滤日志的函数，返回 `false` 的日志将被丢弃。
+    ///
+    /// 适合丢弃无法通过级别或目标区分的特定日志（如第三方库中某一条频繁出现的日志）。
+    /// 过滤函数会在每条日志格式化之前执行，开销高于 [`CloggerBuilder::filter`]，能用元数据区分时应优先使用后者。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use std::sync::Arc;
+    ///
+    /// // 丢弃内容包含 "connection reset" 的日志
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .filter_record(Arc::new(|record| {
+    ///         !record.args().to_string().contains("connection reset")
+    ///     }))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `filter`: 过滤函数。
+    pub fn filter_record(
+        mut self,
+        filter: Arc<dyn Fn(&log::Record) -> bool + Send + Sync>,
+    ) -> Self {
+        self.record_filters.push(filter);
+        self
+    }
+
+    /// 设置在日志交给输出端之前改写日志内容的函数。
+    ///
+    /// 改写函数接收格式化后的日志内容，返回的字符串将替代原来的日志内容写入所有输出端，
+    /// 适合统一空白字符、添加标记或翻译等自定义的改写。每条日志只会改写一次，并且在级别过滤之后执行，
+    /// 被过滤的日志不会调用改写函数；[`CloggerBuilder::filter_record`] 中的过滤函数看到的是改写之前的日志内容。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    /// use std::sync::Arc;
+    ///
+    /// // 将连续的空白字符合并为一个空格
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .message_transform(Arc::new(|message| {
+    ///         message.split_whitespace().collect::<Vec<_>>().join(" ")
+    ///     }))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `transform`: 改写函数。
+    pub fn message_transform(
+        mut self,
+        transform: Arc<dyn Fn(&str) -> String + Send + Sync>,
+    ) -> Self {
+        self.message_transform = Some(transform);
+        self
+    }
+
+    /// 设置初始化完成时输出的提示。
+    ///
+    /// 默认输出 `CLogger 初始化完成 (ง •_•)ง`（纯文本模式下为 `CLogger initialized`）。
+    /// 传入 `Some` 将替换提示的内容，传入 `None` 则不输出提示。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .startup_message(Some("MyApp 日志系统已就绪".to_string()))
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `message`: 提示内容，为 `None` 时不输出提示。
+    pub fn startup_message(mut self, message: Option<String>) -> Self {
+        self.startup_message = Some(message);
+        self
+    }
+
+    /// 设置初始化提示的日志级别，默认为 `Info`。
+    ///
+    /// # 参数
+    /// - `level`: 初始化提示的日志级别。
+    pub fn startup_level(mut self, level: Level) -> Self {
+        self.startup_level = level;
+        self
+    }
+
+    /// 按照当前配置初始化 CLogger。
+    ///
+    /// 与 [`init_clogger`](crate::init_clogger) 相同，CLogger 只会被初始化一次，之后的调用将被忽略，
+    /// 并输出一条警告（请求的日志文件与正在使用的不同时会一并说明），已有的配置不会改变。
+    ///
+    /// 部署时可以通过环境变量覆盖代码中的配置，环境变量的优先级高于代码中的设置：
+    /// - `CLOGGER_FILE`: 日志文件的保存位置，覆盖 [`CloggerBuilder::log_file`]。
+    /// - `CLOGGER_LEVEL`（或 `RUST_LOG`）: 日志级别，覆盖 [`CloggerBuilder::level`]。
+    pub fn init(mut self) {
+        if !self.init_once() {
+            self.warn_reinit();
+        }
+    }
+
+    /// 按照当前配置初始化 CLogger，返回是否由本次调用完成了初始化，已经初始化过时不做任何改变。
+    pub(crate) fn init_once(&mut self) -> bool {
+        if let Some(path) = std::env::var("CLOGGER_FILE")
+            .ok()
+            .filter(|path| !path.is_empty())
+        {
+            self.log_file_path = Some(path);
+        }
+        static INIT: Once = Once::new();
+        let mut initialized_now = false;
+        INIT.call_once(|| {
+            initialized_now = true;
+            let file = self.open_log_file();
+            if let Some(file) = &file {
+                let _ = ACTIVE_FILE.set(file.clone());
+            }
+
+            set_current_config(self.snapshot());
+            set_target_shown(self.show_target);
+            set_relative_location(self.relative_location);
+            set_file_schema(schema_object(&self.file_format()));
+            if self.print_summary {
+                set_shutdown_summary(Summary {
+                    plain: self.plain,
+                    color: self.color(std::io::stderr().is_terminal()),
+                    theme: self.theme.clone(),
+                });
+            }
+            let levels = self.sink_levels();
+            set_global_levels(levels.clone());
+            let (max_level, logger) = self.dispatch(file, &levels, true).into_log();
+            set_global_logger(logger, self.plain);
+            // 基础管线不限制级别，全局的最大级别仍按配置的级别设置，使被过滤的日志在 `log` 宏中被跳过
+            log::set_max_level(max_level.min(self.global_level()));
+            toggle::mark_initialized();
+            let startup_message = match &self.startup_message {
+                Some(message) => message.as_deref(),
+                None if self.plain => Some("CLogger initialized"),
+                None => Some("CLogger 初始化完成 (ง •_•)ง"),
+            };
+            if let Some(message) = startup_message {
+                c_log_at!(self.startup_level, message);
+            }
+            self.warn_invalid_env_level();
+        });
+        initialized_now
+    }
+
+    /// 输出环境变量中无法解析或被忽略的日志级别设置的警告。
+    fn warn_invalid_env_level(&self) {
+        let env = parse_env_level(|name| std::env::var(name).ok());
+        for (name, value) in env.invalid {
+            let message = match (self.plain, value.contains('=')) {
+                (true, true) => format!(
+                    "per-module level \"{}\" in {} is not supported and was ignored",
+                    value, name
+                ),
+                (true, false) => format!("invalid log level \"{}\" in {} was ignored", value, name),
+                (false, true) => format!(
+                    "CLogger 不支持按模块设置级别，{} 中的 \"{}\" 已被忽略",
+                    name, value
+                ),
+                (false, false) => format!("{} 中的日志级别 \"{}\" 无法解析，已被忽略", name, value),
+            };
+            c_warn!(message);
+        }
+    }
+
+    /// 输出重复初始化被忽略的警告，请求的日志文件与正在使用的不同时一并说明。
+    fn warn_reinit(&self) {
+        let active = ACTIVE_FILE
+            .get()
+            .and_then(|file| file.path())
+            .map(|path| path.display().to_string());
+        let requested = self.log_file_path.as_deref();
+        let message = match (requested, self.plain) {
+            (Some(requested), true) if Some(requested) != active.as_deref() => format!(
+                "CLogger is already initialized, re-initialization ignored (requested log file {} differs from the active {})",
+                requested,
+                active.as_deref().unwrap_or("none")
+            ),
+            (Some(requested), false) if Some(requested) != active.as_deref() => format!(
+                "CLogger 已经初始化，本次初始化被忽略（请求的日志文件 {} 与正在使用的 {} 不同）",
+                requested,
+                active.as_deref().unwrap_or("无")
+            ),
+            (_, true) => "CLogger is already initialized, re-initialization ignored".to_string(),
+            (_, false) => "CLogger 已经初始化，本次初始化被忽略".to_string(),
+        };
+        c_warn!(message);
+    }
+
+    /// 按照当前配置创建一个独立的 [`Clogger`] 实例。
+    ///
+    /// 与 [`CloggerBuilder::init`] 不同，该方法不会将 CLogger 设置为全局的 `log` 日志器，
+    /// 因此可以在同一进程中创建多个配置互不相同的实例（例如每个子系统写入不同的日志文件）。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::CloggerBuilder;
+    ///
+    /// let logger = CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example_subsystem.log")
+    ///     .build();
+    /// logger.log("example::moudle_name", "这是一条来自子系统的日志！(づ｡◕‿‿◕｡)づ");
+    /// ```
+    pub fn build(self) -> Clogger {
+        let (level, logger) = self
+            .dispatch(self.open_log_file(), &self.sink_levels(), false)
+            .into_log();
+        Clogger::new(level, logger, self.show_target)
+    }
+}
+
+impl CloggerBuilder {
+    /// 打开配置的日志文件。
+    fn open_log_file(&self) -> Option<Arc<LogFile>> {
+        let file = match (&self.log_file_handle, &self.log_file_path) {
+            (Some(handle), _) => match handle
+                .try_clone()
+                .and_then(|file| LogFile::from_file(file, self.file_options))
+            {
+                Ok(file) => file,
+                Err(e) => {
+                    eprintln!("CLogger 无法使用传入的日志文件，已跳过该输出端: {}", e);
+                    return None;
+                }
+            },
+            (None, Some(path)) => LogFile::open(Path::new(path), self.file_options).unwrap(),
+            (None, None) => return None,
+        };
+        Some(self.track_file(file))
+    }
+
+    /// 获取单条日志内容的长度上限（字节）。
+    fn record_len_limit(&self) -> usize {
+        self.max_record_len.unwrap_or(DEFAULT_MAX_RECORD_LEN)
+    }
+
+    /// 记录 CLogger 写入的日志文件，使其可以被 [`flush_clogger`](crate::flush_clogger) 与定期刷新线程刷新。
+    fn track_file(&self, file: LogFile) -> Arc<LogFile> {
+        let file = Arc::new(file);
+        register_file(&file);
+        if let Some(interval) = self.flush_interval {
+            spawn_flusher(&file, interval);
+        }
+        file
+    }
+
+    /// 获取实际生效的全局日志级别，环境变量中的级别优先。
+    fn global_level(&self) -> LevelFilter {
+        env_level().unwrap_or(self.level)
+    }
+
+    /// 获取当前配置实际生效的结果，用于 [`current_config`](crate::current_config)。
+    fn snapshot(&self) -> CloggerConfig {
+        let mut sinks = Vec::new();
+        let mut add = |enabled: bool, name: &str| {
+            if enabled {
+                sinks.push(name.to_string());
+            }
+        };
+        add(self.stdout_level != LevelFilter::Off, "stdout");
+        add(
+            self.stderr_level.is_some() || self.routes.contains(&Sink::Stderr),
+            "stderr",
+        );
+        add(self.console_capture_path.is_some(), "console_capture");
+        add(
+            self.log_file_path.is_some() || self.log_file_handle.is_some(),
+            "file",
+        );
+        add(!self.target_files.is_empty(), "target_file");
+        add(!self.level_files.is_empty(), "level_file");
+        add(self.sink_matrix.is_some(), "sink_matrix");
+        #[cfg(feature = "binary")]
+        add(self.binary_path.is_some(), "binary_file");
+        #[cfg(feature = "sqlite")]
+        add(self.sqlite_path.is_some(), "sqlite");
+        #[cfg(all(target_os = "linux", feature = "journald"))]
+        add(self.journald_identifier.is_some(), "journald");
+        #[cfg(unix)]
+        add(self.unix_socket_path.is_some(), "unix_socket");
+        #[cfg(all(windows, feature = "windows-eventlog"))]
+        add(self.event_source.is_some(), "windows_event_log");
+        CloggerConfig {
+            level: self.global_level(),
+            level_from_env: env_level().is_some(),
+            stdout_level: self.stdout_level,
+            stderr_level: self.stderr_level,
+            file_level: self.file_level,
+            log_file: self.log_file_path.as_ref().map(Into::into),
+            target_files: self
+                .target_files
+                .iter()
+                .map(|route| (route.prefix.clone(), route.path.clone()))
+                .collect(),
+            level_files: self.level_files.clone(),
+            sinks,
+            stdout_color: self.color(std::io::stdout().is_terminal()),
+            stderr_color: self.color(std::io::stderr().is_terminal()),
+            timestamp_mode: self.timestamp_mode.clone(),
+            filter_count: self.filters.len() + self.record_filters.len(),
+        }
+    }
+
+    /// 创建保存当前配置中可在运行时调整的日志级别的 [`SinkLevels`]。
+    fn sink_levels(&self) -> Arc<SinkLevels> {
+        Arc::new(SinkLevels::new(
+            self.global_level(),
+            self.stdout_level,
+            self.file_level,
+        ))
+    }
+
+    /// 根据当前配置构建完整的日志输出管线，`global` 为是否用于全局的 CLogger。
+    ///
+    /// 全局、标准输出与日志文件的级别从 `levels` 中读取，因此可以在运行时调整。
+    fn dispatch(
+        &self,
+        file: Option<Arc<LogFile>>,
+        levels: &Arc<SinkLevels>,
+        global: bool,
+    ) -> Dispatch {
+        // 输出到终端
+        let routes = self.routes;
+        let mut stdout = self
+            .console_format(self.color(std::io::stdout().is_terminal()))
+            .into_dispatch()
+            .filter({
+                let levels = levels.clone();
+                move |metadata| metadata.level() <= levels.stdout()
+            })
+            .filter(move |metadata| routes[metadata.level() as usize - 1] == Sink::Stdout)
+            .chain(self.output("stdout", self.stdout_writer()));
+        if let Some(path) = &self.console_capture_path {
+            // 将终端输出原样写入副本文件
+            let options = FileOptions {
+                wrap_size: None,
+                ..self.file_options
+            };
+            match LogFile::open(Path::new(path), options) {
+                Ok(file) => {
+                    let capture = self.track_file(file);
+                    stdout = stdout.chain(self.output("console", LogFileWriter::new(capture)));
+                }
+                Err(e) => eprintln!(
+                    "CLogger 无法打开终端输出的副本文件 {}，已跳过该输出端: {}",
+                    path, e
+                ),
+            }
+        }
+        // 级别在过滤函数中判断，使 `c_verbose_scope!` 可以临时提高单个线程的级别
+        let global_levels = levels.clone();
+        let mut base_config = Dispatch::new()
+            .filter(move |metadata| metadata.level() <= effective_level(global_levels.global()))
+            .filter(|_| is_enabled())
+            .chain(stdout);
+        for filter in &self.filters {
+            let filter = filter.clone();
+            base_config = base_config.filter(move |metadata| filter(metadata));
+        }
+
+        if self.stderr_level.is_some() || routes.contains(&Sink::Stderr) {
+            // 输出到标准错误
+            let stderr_level = self.stderr_level.unwrap_or(LevelFilter::Off);
+            base_config = base_config.chain(
+                self.console_format(self.color(std::io::stderr().is_terminal()))
+                    .into_dispatch()
+                    .filter(move |metadata| {
+                        routes[metadata.level() as usize - 1] == Sink::Stderr
+                            || metadata.level() <= stderr_level
+                    })
+                    .chain(self.output("stderr", self.stderr_writer())),
+            );
+        }
+
+        let exclusive: Vec<String> = self
+            .target_files
+            .iter()
+            .filter(|route| route.exclusive)
+            .map(|route| route.prefix.clone())
+            .collect();
+        if let Some(file) = file {
+            // 写入日志文件（不带颜色），被独占路由的日志不会写入
+            let output = Arc::new(self.file_output("file", file));
+            if global {
+                set_file_output(output.clone());
+            }
+            base_config =
+                base_config.chain(self.file_dispatch(output, levels).filter(move |metadata| {
+                    let (module, _) = split_target(metadata.target());
+                    !exclusive.iter().any(|prefix| module.starts_with(prefix))
+                }));
+        }
+
+        for route in &self.target_files {
+            // 按模块名称的前缀写入单独的日志文件
+            let file = match LogFile::open(Path::new(&route.path), self.file_options) {
+                Ok(file) => self.track_file(file),
+                Err(e) => {
+                    eprintln!(
+                        "CLogger 无法打开模块 {} 的日志文件 {}，已跳过该输出端: {}",
+                        route.prefix, route.path, e
+                    );
+                    continue;
+                }
+            };
+            let prefix = route.prefix.clone();
+            base_config = base_config.chain(
+                self.file_dispatch(self.file_output("target-file", file), levels)
+                    .filter(move |metadata| split_target(metadata.target()).0.starts_with(&prefix)),
+            );
+        }
+
+        for (level, path) in &self.level_files {
+            // 按日志级别写入单独的日志文件，只包含级别完全相同的日志
+            let file = match LogFile::open(Path::new(path), self.file_options) {
+                Ok(file) => self.track_file(file),
+                Err(e) => {
+                    eprintln!(
+                        "CLogger 无法打开 {} 级别的日志文件 {}，已跳过该输出端: {}",
+                        level, path, e
+                    );
+                    continue;
+                }
+            };
+            let level = *level;
+            base_config = base_config.chain(
+                self.file_dispatch(self.file_output("level-file", file), levels)
+                    .filter(move |metadata| metadata.level() == level),
+            );
+        }
+
+        if let Some(matrix) = &self.sink_matrix {
+            // 按照路由表将各级别的日志写入对应的目标
+            let mut matrix_files: HashMap<&str, Arc<LogFile>> = HashMap::new();
+            for route in &matrix.routes {
+                let output = match &route.destination.kind {
+                    DestinationKind::Stdout => self
+                        .route_console_format(&route.format, std::io::stdout().is_terminal())
+                        .into_dispatch()
+                        .chain(self.output("matrix-stdout", self.stdout_writer())),
+                    DestinationKind::Stderr => self
+                        .route_console_format(&route.format, std::io::stderr().is_terminal())
+                        .into_dispatch()
+                        .chain(self.output("matrix-stderr", self.stderr_writer())),
+                    DestinationKind::File(path) => {
+                        // 多条路由写入同一个文件时共享同一个文件句柄，避免各自的缓冲区交错写入
+                        let file = match matrix_files.get(path.as_str()) {
+                            Some(file) => Arc::clone(file),
+                            None => match LogFile::open(Path::new(path), self.file_options) {
+                                Ok(file) => {
+                                    let file = self.track_file(file);
+                                    matrix_files.insert(path.as_str(), file.clone());
+                                    file
+                                }
+                                Err(e) => {
+                                    eprintln!(
+                                        "CLogger 无法打开路由表中的日志文件 {}，已跳过该路由: {}",
+                                        path, e
+                                    );
+                                    continue;
+                                }
+                            },
+                        };
+                        let format = Format {
+                            ansi_stripper: self.file_ansi_stripper.clone(),
+                            ..route.format.apply(self.format(false))
+                        };
+                        format
+                            .into_dispatch()
+                            .chain(self.output("matrix-file", LogFileWriter::new(file)))
+                    }
+                    DestinationKind::Writer(writer) => route
+                        .format
+                        .apply(self.format(false))
+                        .into_dispatch()
+                        .chain(self.output("matrix-writer", writer.clone())),
+                };
+                let levels = route.levels;
+                base_config = base_config
+                    .chain(output.filter(move |metadata| levels[metadata.level() as usize - 1]));
+            }
+        }
+
+        #[cfg(feature = "binary")]
+        if let Some(path) = &self.binary_path {
+            // 以二进制格式写入日志文件
+            match BinarySink::open(
+                Path::new(path),
+                self.file_options,
+                self.clock.clone(),
+                self.record_len_limit(),
+            ) {
+                Ok(sink) => {
+                    let levels = levels.clone();
+                    base_config = base_config.chain(
+                        Dispatch::new()
+                            .filter(move |metadata| metadata.level() <= levels.file())
+                            .chain(Box::new(sink) as Box<dyn log::Log>),
+                    );
+                }
+                Err(e) => eprintln!(
+                    "CLogger 无法打开二进制日志文件 {}，已跳过该输出端: {}",
+                    path, e
+                ),
+            }
+        }
+
+        #[cfg(feature = "sqlite")]
+        if let Some(path) = &self.sqlite_path {
+            // 写入 SQLite 数据库
+            match SqliteSink::open(
+                path,
+                self.clock.clone(),
+                self.global_fields.clone().into(),
+                self.field_mask(),
+                self.sqlite_flat_location,
+                self.record_len_limit(),
+            ) {
+                Ok(sink) => {
+                    let levels = levels.clone();
+                    base_config = base_config.chain(
+                        Dispatch::new()
+                            .filter(move |metadata| metadata.level() <= levels.file())
+                            .chain(Box::new(sink) as Box<dyn log::Log>),
+                    );
+                }
+                Err(e) => eprintln!(
+                    "CLogger 无法打开 SQLite 数据库 {}，已跳过该输出端: {}",
+                    path, e
+                ),
+            }
+        }
+
+        #[cfg(all(target_os = "linux", feature = "journald"))]
+        if let Some(identifier) = &self.journald_identifier {
+            // 发送到 systemd 日志
+            match JournaldSink::open(
+                Path::new(JOURNAL_SOCKET),
+                identifier,
+                self.record_len_limit(),
+            ) {
+                Ok(sink) => base_config = base_config.chain(Box::new(sink) as Box<dyn log::Log>),
+                Err(e) => eprintln!("CLogger 无法连接到 journald，已跳过该输出端: {}", e),
+            }
+        }
+
+        #[cfg(all(windows, feature = "windows-eventlog"))]
+        if let Some(source) = &self.event_source {
+            // 写入 Windows 事件日志
+            let sink = EventLogSink::open(source).unwrap();
+            base_config = base_config.chain(Box::new(sink) as Box<dyn log::Log>);
+        }
+
+        #[cfg(unix)]
+        if let Some(path) = &self.unix_socket_path {
+            // 写入 Unix 域套接字
+            let sink = self.output("socket", UnixSocketSink::new(path));
+            base_config = base_config.chain(self.format(false).into_dispatch().chain(sink));
+        }
+
+        #[cfg(feature = "test-util")]
+        {
+            // 供 `TestLogger` 捕获当前线程的日志（不带颜色）
+            base_config = base_config.chain(
+                self.format(false)
+                    .into_dispatch()
+                    .chain(Box::new(CaptureSink) as Box<dyn log::Log>),
+            );
+        }
+
+        if self.record_filters.is_empty()
+            && self.message_transform.is_none()
+            && !self.ordered
+            && !self.dedupe_consecutive
+            && !global
+        {
+            return base_config;
+        }
+        let (_, mut sinks) = base_config.into_log();
+        if self.ordered {
+            sinks = Box::new(Ordered {
+                lock: Mutex::new(()),
+                sinks,
+            });
+        }
+        if self.dedupe_consecutive {
+            sinks = Box::new(Dedupe::new(sinks, self.plain, self.record_len_limit()));
+        }
+        if let Some(transform) = &self.message_transform {
+            sinks = Box::new(MessageTransform {
+                transform: transform.clone(),
+                max_record_len: self.record_len_limit(),
+                sinks,
+            });
+        }
+        if global {
+            // 统计全局 CLogger 各级别的日志条数，重复的日志在合并之前逐条统计
+            sinks = Box::new(LevelCounter::new(sinks));
+        }
+        if !self.record_filters.is_empty() {
+            // 按日志内容过滤需要完整的日志记录，因此在所有输出端之前统一过滤
+            sinks = Box::new(RecordFilter {
+                filters: self.record_filters.clone(),
+                sinks,
+            });
+        }
+        Dispatch::new().chain(sinks)
+    }
+
+    /// 创建写入日志文件的输出端，启用非阻塞写入时由名为 `name` 的后台线程写入。
+    fn file_output(&self, name: &str, file: Arc<LogFile>) -> FileOutput {
+        // 每个文件只使用一个写入端，达到同步级别的日志写入后立即同步到存储设备
+        if self.non_blocking {
+            FileOutput::non_blocking(name, file, self.sync_level)
+        } else {
+            FileOutput::new(file, self.sync_level)
+        }
+    }
+
+    /// 创建经过 `output` 写入日志文件（不带颜色）的输出管线。
+    fn file_dispatch(&self, output: impl log::Log + 'static, levels: &Arc<SinkLevels>) -> Dispatch {
+        let levels = levels.clone();
+        self.file_format()
+            .into_dispatch()
+            .filter(move |metadata| metadata.level() <= levels.file())
+            .chain(Box::new(output) as Box<dyn log::Log>)
+    }
+
+    /// 判断输出到终端流的日志是否着色，`is_terminal` 为该流自身是否连接到终端。
+    ///
+    /// 每个输出端单独判断，例如标准输出被重定向到文件时，标准错误仍然可以着色。
+    fn color(&self, is_terminal: bool) -> bool {
+        !self.plain && supports_color(|name| std::env::var(name).ok(), is_terminal)
+    }
+
+    /// 根据当前配置创建日志格式。
+    fn format(&self, color: bool) -> Format {
+        Format {
+            color,
+            console: false,
+            app_tag: self.app_tag.clone(),
+            clock: self.clock.clone(),
+            timestamp_mode: self.timestamp_mode.clone(),
+            theme: self.theme.clone(),
+            sanitize_control: self.sanitize_control,
+            target_width: self.target_width,
+            separator: self.separator.clone(),
+            show_crate_version: self.show_crate_version,
+            hide_location: !self.show_location,
+            hide_target: !self.show_target,
+            max_record_len: self.max_record_len,
+            global_fields: self.global_fields.clone().into(),
+            field_mask: self.field_mask(),
+            ansi_stripper: None,
+        }
+    }
+
+    /// 根据当前配置创建终端（标准输出与标准错误）使用的日志格式。
+    fn console_format(&self, color: bool) -> Format {
+        Format {
+            console: true,
+            hide_location: !self.console_location.unwrap_or(self.show_location),
+            ..self.format(color)
+        }
+    }
+
+    /// 根据当前配置创建路由表中输出到终端的日志格式，`is_terminal` 为该流自身是否连接到终端。
+    fn route_console_format(&self, format: &SinkFormat, is_terminal: bool) -> Format {
+        Format {
+            // 设置为不着色的路由不受 `set_colors` 的影响
+            console: format.color(true),
+            ..format.apply(self.format(format.color(self.color(is_terminal))))
+        }
+    }
+
+    /// 根据当前配置创建日志文件使用的日志格式。
+    fn file_format(&self) -> Format {
+        Format {
+            hide_location: !self.file_location.unwrap_or(self.show_location),
+            ansi_stripper: self.file_ansi_stripper.clone(),
+            ..self.format(false)
+        }
+    }
+
+    /// 根据当前配置创建隐藏键值对的规则。
+    fn field_mask(&self) -> FieldMask {
+        FieldMask::new(&self.masked_fields, self.mask_case_sensitive)
+    }
+
+    /// 获取标准输出的写入端。
+    fn stdout_writer(&self) -> Box<dyn Write + Send> {
+        #[cfg(test)]
+        if let Some((stdout, _)) = &self.test_console {
+            return Box::new(stdout.clone());
+        }
+        // 管道被关闭（如输出给 `head`）后停止写入标准输出
+        let stdout = BrokenPipeGuard::new(std::io::stdout());
+        match &self.progress_redraw {
+            Some(redraw) if std::io::stdout().is_terminal() => {
+                Box::new(ProgressWriter::new(stdout, redraw.clone()))
+            }
+            _ => Box::new(stdout),
+        }
+    }
+
+    /// 获取标准错误的写入端。
+    fn stderr_writer(&self) -> Box<dyn Write + Send> {
+        #[cfg(test)]
+        if let Some((_, stderr)) = &self.test_console {
+            return Box::new(stderr.clone());
+        }
+        Box::new(BrokenPipeGuard::new(std::io::stderr()))
+    }
+
+    /// 将写入端包装为 fern 的输出端，启用非阻塞写入时会交由后台线程写入。
+    fn output<W: Write + Send + 'static>(&self, name: &str, writer: W) -> Output {
+        let writer: Box<dyn Write + Send> = if self.non_blocking {
+            Box::new(spawn_writer(name, writer))
+        } else {
+            Box::new(writer)
+        };
+        writer.into()
+    }
+}
+
+impl Default for CloggerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 根据日志元数据过滤日志的函数，用于 [`CloggerBuilder::filter`]。
+type MetadataFilter = Arc<dyn Fn(&log::Metadata) -> bool + Send + Sync>;
+
+/// 根据完整的日志记录过滤日志的函数，用于 [`CloggerBuilder::filter_record`]。
+type RecordFilterFn = Arc<dyn Fn(&log::Record) -> bool + Send + Sync>;
+
+/// 在日志交给输出端之前，按照完整的日志记录进行过滤。
+struct RecordFilter {
+    filters: Vec<RecordFilterFn>,
+    sinks: Box<dyn log::Log>,
+}
+
+impl log::Log for RecordFilter {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.sinks.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.filters.iter().all(|filter| filter(record)) {
+            self.sinks.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.sinks.flush();
+    }
+}
+
+/// 改写日志内容的函数，用于 [`CloggerBuilder::message_transform`]。
+type MessageTransformFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// 在日志交给输出端之前改写日志内容。
+///
+/// 改写之前的日志内容最多保留 `max_record_len` 字节。
+struct MessageTransform {
+    transform: MessageTransformFn,
+    max_record_len: usize,
+    sinks: Box<dyn log::Log>,
+}
+
+impl log::Log for MessageTransform {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.sinks.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        // 被过滤的日志不需要改写
+        if !self.sinks.enabled(record.metadata()) {
+            return;
+        }
+        let message = (self.transform)(&capped_message(record.args(), self.max_record_len));
+        self.sinks.log(
+            &record
+                .to_builder()
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+
+    fn flush(&self) {
+        self.sinks.flush();
+    }
+}
+
+/// 在同一个锁内将日志依次写入所有输出端，使各输出端中的日志顺序一致。
+struct Ordered {
+    lock: Mutex<()>,
+    sinks: Box<dyn log::Log>,
+}
+
+impl log::Log for Ordered {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.sinks.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        let _lock = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.sinks.log(record);
+    }
+
+    fn flush(&self) {
+        self.sinks.flush();
+    }
+}
+
+/// 按模块名称前缀路由的日志文件，用于 [`CloggerBuilder::target_file`]。
+struct TargetFile {
+    prefix: String,
+    path: String,
+    exclusive: bool,
+}
+
+/// 终端输出的目标流，用于 [`CloggerBuilder::route`]。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sink {
+    /// 标准输出。
+    Stdout,
+    /// 标准错误。
+    Stderr,
+}
+
+/// 根据环境变量与输出流是否为终端判断是否着色，判断顺序见 [`CloggerBuilder::plain`]。
+///
+/// `var` 用于读取环境变量，`is_terminal` 为输出流自身是否连接到终端。
+fn supports_color(var: impl Fn(&str) -> Option<String>, is_terminal: bool) -> bool {
+    if var("CLICOLOR_FORCE").is_some_and(|force| force != "0") {
+        return true;
+    }
+    if var("NO_COLOR").is_some() {
+        return false;
+    }
+    let term_supports_color = match var("TERM") {
+        Some(term) => term != "dumb",
+        // Windows 的控制台通常不设置 `TERM`
+        None => !cfg!(unix),
+    };
+    term_supports_color && var("CLICOLOR").is_none_or(|clicolor| clicolor != "0") && is_terminal
+}
+
+/// 读取 `CLOGGER_LEVEL` 或 `RUST_LOG` 环境变量中的日志级别，`CLOGGER_LEVEL` 优先，均未设置时返回 `None`。
+fn env_level() -> Option<LevelFilter> {
+    parse_env_level(|name| std::env::var(name).ok()).level
+}
+
+/// 从环境变量中解析出的日志级别，见 [`parse_env_level`]。
+#[derive(Debug, Default, PartialEq)]
+struct EnvLevel {
+    /// 全局日志级别。
+    level: Option<LevelFilter>,
+    /// 无法解析或不受支持的设置，元素为 `(环境变量名称, 设置内容)`。
+    invalid: Vec<(&'static str, String)>,
+}
+
+/// 解析 `CLOGGER_LEVEL` 与 `RUST_LOG` 环境变量中的日志级别，`var` 用于读取环境变量。
+///
+/// `CLOGGER_LEVEL` 只能是单一的级别名称（如 `debug`），合法时优先使用。`RUST_LOG` 按 `env_logger` 的语法以逗号分隔，
+/// 其中不带模块名称的项（如 `warn,myapp=debug` 中的 `warn`）为全局级别，出现多次时以最后一项为准；
+/// CLogger 不支持按模块设置级别，`myapp=debug` 形式的项会被忽略，`/` 之后的正则过滤同样会被忽略。
+/// 无法解析的值与被忽略的项都会记录在 [`EnvLevel::invalid`] 中，由初始化时输出警告。
+fn parse_env_level(var: impl Fn(&str) -> Option<String>) -> EnvLevel {
+    let mut result = EnvLevel::default();
+    if let Some(value) = var("CLOGGER_LEVEL").filter(|value| !value.trim().is_empty()) {
+        match value.trim().parse() {
+            Ok(level) => {
+                result.level = Some(level);
+                return result;
+            }
+            Err(_) => result.invalid.push(("CLOGGER_LEVEL", value)),
+        }
+    }
+    let Some(value) = var("RUST_LOG") else {
+        return result;
+    };
+    // 正则过滤位于第一个 `/` 之后，作用于整个设置
+    let directives = value
+        .split_once('/')
+        .map_or(value.as_str(), |(directives, _)| directives);
+    for directive in directives
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        match directive.parse() {
+            Ok(level) if !directive.contains('=') => result.level = Some(level),
+            _ => result.invalid.push(("RUST_LOG", directive.to_string())),
+        }
+    }
+    result
+}
+
+/// 将命令行中 `-v` 参数的出现次数转换为日志级别。
+///
+/// 对应关系为：`0` => `Warn`、`1` => `Info`、`2` => `Debug`、`3` 及以上 => `Trace`。
+/// 若 `CLOGGER_LEVEL` 或 `RUST_LOG` 环境变量中设置了全局级别，其优先级高于由此得到的级别（详见 [`CloggerBuilder::level`]）。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{level_from_verbosity, CloggerBuilder};
+///
+/// let verbosity = 2; // 例如用户传入了 `-vv`
+/// CloggerBuilder::new()
+///     .log_file("/tmp/clogger_example.log")
+///     .level(level_from_verbosity(verbosity))
+///     .init();
+/// ```
+///
+/// # 参数
+/// - `count`: `-v` 参数的出现次数。
+pub fn level_from_verbosity(count: u8) -> LevelFilter {
+    match count {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{Destination, SinkFormat};
+    use crate::Clogger;
+    use chrono::TimeZone;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_level_from_verbosity() {
+        assert_eq!(level_from_verbosity(0), LevelFilter::Warn);
+        assert_eq!(level_from_verbosity(1), LevelFilter::Info);
+        assert_eq!(level_from_verbosity(2), LevelFilter::Debug);
+        assert_eq!(level_from_verbosity(3), LevelFilter::Trace);
+        assert_eq!(level_from_verbosity(u8::MAX), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_parse_env_level() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let parse = |vars| parse_env_level(env(vars));
+        assert_eq!(parse(&[]), EnvLevel::default());
+        assert_eq!(
+            parse(&[("CLOGGER_LEVEL", "debug"), ("RUST_LOG", "info")]).level,
+            Some(LevelFilter::Debug)
+        );
+        assert_eq!(
+            parse(&[("RUST_LOG", " Trace ")]).level,
+            Some(LevelFilter::Trace)
+        );
+        // 全局项与按模块设置的项混合时使用全局项，按模块设置的项被忽略
+        assert_eq!(
+            parse(&[("RUST_LOG", "myapp=debug,warn,hyper=off/foo")]),
+            EnvLevel {
+                level: Some(LevelFilter::Warn),
+                invalid: vec![
+                    ("RUST_LOG", "myapp=debug".to_string()),
+                    ("RUST_LOG", "hyper=off".to_string())
+                ],
+            }
+        );
+        assert_eq!(
+            parse(&[("RUST_LOG", "myapp=debug")]),
+            EnvLevel {
+                level: None,
+                invalid: vec![("RUST_LOG", "myapp=debug".to_string())],
+            }
+        );
+        // 无法解析的 `CLOGGER_LEVEL` 不会被静默跳过
+        assert_eq!(
+            parse(&[("CLOGGER_LEVEL", "loud"), ("RUST_LOG", "error")]),
+            EnvLevel {
+                level: Some(LevelFilter::Error),
+                invalid: vec![("CLOGGER_LEVEL", "loud".to_string())],
+            }
+        );
+        assert_eq!(
+            parse(&[("CLOGGER_LEVEL", " "), ("RUST_LOG", "myapp")]),
+            EnvLevel {
+                level: None,
+                invalid: vec![("RUST_LOG", "myapp".to_string())],
+            }
+        );
+    }
+
+    #[test]
+    fn test_supports_color() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert!(supports_color(env(&[("TERM", "xterm-256color")]), true));
+        assert!(!supports_color(env(&[("TERM", "dumb")]), true));
+        assert!(!supports_color(
+            env(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "0")]),
+            true
+        ));
+        assert!(supports_color(
+            env(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "1")]),
+            true
+        ));
+        assert_eq!(supports_color(env(&[]), true), !cfg!(unix));
+        assert!(!supports_color(
+            env(&[("TERM", "xterm"), ("NO_COLOR", "1")]),
+            true
+        ));
+        assert!(!supports_color(
+            env(&[("TERM", "xterm"), ("CLICOLOR", "0")]),
+            true
+        ));
+        // 每个输出流单独判断是否为终端
+        assert!(!supports_color(env(&[("TERM", "xterm")]), false));
+        assert!(supports_color(env(&[("CLICOLOR_FORCE", "1")]), false));
+    }
+
+    #[test]
+    fn test_sink_level_matrix() {
+        let path = std::env::temp_dir().join(format!("clogger_matrix_{}.log", std::process::id()));
+        let stdout = MemoryWriter::default();
+        let stderr = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .log_file(path.to_str().unwrap())
+            .level(LevelFilter::Trace)
+            .plain(true)
+            .stdout_level(LevelFilter::Info)
+            .stderr_level(LevelFilter::Warn)
+            .file_level(LevelFilter::Debug);
+        builder.test_console = Some((stdout.clone(), stderr.clone()));
+        let logger: Clogger = builder.build();
+
+        logger.error("clogger::tests", "error");
+        logger.warn("clogger::tests", "warn");
+        logger.log("clogger::tests", "info");
+        logger.debug("clogger::tests", "debug");
+
+        let file = std::fs::read_to_string(&path).unwrap();
+        let (stdout, stderr) = (stdout.contents(), stderr.contents());
+        // (级别, 标准输出, 标准错误, 日志文件)
+        for (message, in_stdout, in_stderr, in_file) in [
+            ("error", true, true, true),
+            ("warn", true, true, true),
+            ("info", true, false, true),
+            ("debug", false, false, true),
+        ] {
+            let line = format!("] {}\n", message);
+            assert_eq!(stdout.contains(&line), in_stdout, "stdout: {}", message);
+            assert_eq!(stderr.contains(&line), in_stderr, "stderr: {}", message);
+            assert_eq!(file.contains(&line), in_file, "file: {}", message);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compact() {
+        let builder = CloggerBuilder::new()
+            .compact()
+            .clock(|| Local.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap());
+        let line = builder.format(false).render(
+            &format_args!("服务已启动"),
+            &log::Record::builder()
+                .level(Level::Info)
+                .target("app::server (src/server.rs:12^5)")
+                .build(),
+        );
+        assert_eq!(line, "(12:00:00) [I] [app::server]      服务已启动");
+    }
+
+    #[test]
+    fn test_reliable() {
+        let builder = CloggerBuilder::new().reliable().sync_level(Level::Error);
+        assert!(builder.file_options.buffered);
+        assert_eq!(builder.flush_interval, Some(Duration::from_secs(1)));
+        // 之后的调用可以覆盖预设中的设置
+        assert_eq!(builder.sync_level, Some(Level::Error));
+        assert_eq!(
+            CloggerBuilder::new().reliable().sync_level,
+            Some(Level::Warn)
+        );
+    }
+
+    #[test]
+    fn test_reliable_files() {
+        let dir = std::env::temp_dir();
+        let path = |name: &str| {
+            dir.join(format!(
+                "clogger_reliable_{}_{}.log",
+                name,
+                std::process::id()
+            ))
+        };
+        let (app, audit, info) = (path("app"), path("audit"), path("info"));
+        let logger = CloggerBuilder::new()
+            .stdout_level(LevelFilter::Off)
+            .log_file(app.to_str().unwrap())
+            .target_file("audit::", audit.to_str().unwrap(), true)
+            .level_file(Level::Info, info.to_str().unwrap())
+            .reliable()
+            .build();
+        let read = |path| std::fs::read_to_string(path).unwrap();
+
+        // 警告写入后，之前缓冲的日志与警告都已写入文件
+        logger.log("app", "服务已启动");
+        logger.warn("app", "磁盘空间不足");
+        assert!(read(&app).contains("服务已启动") && read(&app).contains("磁盘空间不足"));
+
+        // 路由的文件同样会被定期刷新
+        logger.log("audit::login", "用户已登录");
+        std::thread::sleep(Duration::from_millis(1500));
+        assert!(read(&audit).contains("用户已登录"));
+        assert!(read(&info).contains("服务已启动"));
+
+        logger.log("audit::login", "用户已退出");
+        crate::flush_clogger();
+        assert!(read(&audit).contains("用户已退出"));
+        for path in [&app, &audit, &info] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let config = CloggerBuilder::new()
+            .plain(true)
+            .stdout_level(LevelFilter::Info)
+            .log_file("/tmp/clogger_snapshot.log")
+            .target_file("audit::", "/tmp/clogger_snapshot_audit.log", true)
+            .only_targets(&["app"])
+            .timestamp_mode(TimestampMode::Utc)
+            .snapshot();
+        assert_eq!(config.stdout_level, LevelFilter::Info);
+        assert_eq!(config.sinks, ["stdout", "file", "target_file"]);
+        assert_eq!(
+            config.target_files,
+            [(
+                "audit::".to_string(),
+                "/tmp/clogger_snapshot_audit.log".to_string()
+            )]
+        );
+        assert!(!config.stdout_color && !config.stderr_color);
+        assert_eq!(config.timestamp_mode, TimestampMode::Utc);
+        assert_eq!(config.filter_count, 1);
+    }
+
+    #[test]
+    fn test_simulated_clock() {
+        use std::sync::atomic::{AtomicI64, Ordering};
+
+        let simulated = Arc::new(AtomicI64::new(0));
+        let start = Local.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let time = simulated.clone();
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .clock(move || start + chrono::Duration::seconds(time.load(Ordering::Relaxed)));
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        logger.log("sim", "第一步");
+        simulated.fetch_add(3600, Ordering::Relaxed);
+        logger.log("sim", "第二步");
+
+        let lines: Vec<String> = stdout.contents().lines().map(str::to_string).collect();
+        assert!(lines[0].starts_with("(2024-06-01 00:00:00.000) ") && lines[0].ends_with("第一步"));
+        assert!(lines[1].starts_with("(2024-06-01 01:00:00.000) ") && lines[1].ends_with("第二步"));
+    }
+
+    #[test]
+    fn test_verbose_scope() {
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new().plain(true).level(LevelFilter::Info);
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        logger.debug("app", "作用域之外的调试输出");
+        {
+            let _verbose = crate::VerboseGuard::new(LevelFilter::Debug);
+            logger.debug("app", "作用域之内的调试输出");
+        }
+        logger.debug("app", "离开作用域后的调试输出");
+
+        let stdout = stdout.contents();
+        assert!(stdout.contains("作用域之内的调试输出"));
+        assert!(
+            !stdout.contains("作用域之外的调试输出") && !stdout.contains("离开作用域后的调试输出")
+        );
+    }
+
+    #[test]
+    fn test_runtime_sink_levels() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_runtime_levels_{}.log", std::process::id()));
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .level(LevelFilter::Info)
+            .stdout_level(LevelFilter::Warn)
+            .file_level(LevelFilter::Info)
+            .log_file(path.to_str().unwrap());
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let levels = builder.sink_levels();
+        let (level, logger) = builder
+            .dispatch(builder.open_log_file(), &levels, false)
+            .into_log();
+        let logger = Clogger::new(level, logger, true);
+
+        logger.debug("app", "调整前的调试输出");
+        logger.log("app", "调整前的常规日志");
+        // 只让日志文件记录调试日志，标准输出保持不变
+        levels.set_global(LevelFilter::Debug);
+        levels.set_file(LevelFilter::Debug);
+        logger.debug("app", "调整后的调试输出");
+        logger.warn("app", "调整后的警告日志");
+        logger.flush();
+
+        let file = std::fs::read_to_string(&path).unwrap();
+        assert!(!file.contains("调整前的调试输出") && file.contains("调整前的常规日志"));
+        assert!(file.contains("调整后的调试输出") && file.contains("调整后的警告日志"));
+        let stdout = stdout.contents();
+        assert!(!stdout.contains("常规日志") && !stdout.contains("调试输出"));
+        assert!(stdout.contains("调整后的警告日志"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_dedupe_consecutive() {
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new().plain(true).dedupe_consecutive(true);
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        for _ in 0..3 {
+            logger.error("app", "连接被拒绝");
+        }
+        // 同一调用位置的相同日志才视为重复
+        let reconnect = || logger.log("app", "重新连接");
+        reconnect();
+        reconnect();
+        logger.flush();
+        reconnect();
+        // 调用位置不同的日志不视为重复
+        logger.log("app", "重新连接");
+        logger.log("app", "连接成功");
+
+        let stdout = stdout.contents();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 7, "{}", stdout);
+        assert!(lines[0].ends_with("连接被拒绝"));
+        assert!(lines[1].contains("[E] [app (") && lines[1].ends_with("(repeated 2 times)"));
+        assert!(lines[2].ends_with("重新连接"));
+        // 刷新时输出尚未输出的重复次数，之后重新开始比较
+        assert!(lines[3].ends_with("(repeated 1 time)"));
+        assert!(lines[4].ends_with("重新连接"));
+        fn site(line: &str) -> &str {
+            let (_, rest) = line.split_once("[app (").unwrap();
+            rest.split_once(')').unwrap().0
+        }
+        assert!(lines[5].ends_with("重新连接"));
+        assert_ne!(site(lines[4]), site(lines[5]));
+        assert!(lines[6].ends_with("连接成功"));
+    }
+
+    #[test]
+    fn test_message_transform() {
+        let stdout = MemoryWriter::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .level(LevelFilter::Info)
+            .message_transform(Arc::new(move |message| {
+                counted.fetch_add(1, Ordering::Relaxed);
+                message.split_whitespace().collect::<Vec<_>>().join(" ")
+            }));
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        logger.log("app", "连接   已\t建立");
+        // 被过滤的日志不会调用改写函数
+        logger.debug("app", "调试信息");
+
+        assert!(stdout.contents().trim_end().ends_with("] 连接 已 建立"));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_message_transform_max_record_len() {
+        let lengths = Arc::new(Mutex::new(Vec::new()));
+        let seen = lengths.clone();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .max_record_len(16)
+            .message_transform(Arc::new(move |message| {
+                seen.lock().unwrap().push(message.len());
+                message.to_string()
+            }));
+        builder.test_console = Some((MemoryWriter::default(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        // 改写函数收到的内容已经被截断，不会构建完整的超长字符串
+        logger.log("app", "a".repeat(1024 * 1024));
+        assert_eq!(*lengths.lock().unwrap(), [16 + " ...[truncated]".len()]);
+    }
+
+    #[test]
+    fn test_route_to_stderr() {
+        let stdout = MemoryWriter::default();
+        let stderr = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .stderr_level(LevelFilter::Error)
+            .route(Level::Debug, Sink::Stderr);
+        builder.test_console = Some((stdout.clone(), stderr.clone()));
+        let logger = builder.build();
+
+        logger.error("clogger::tests", "error");
+        logger.log("clogger::tests", "info");
+        logger.debug("clogger::tests", "debug");
+
+        let (stdout, stderr) = (stdout.contents(), stderr.contents());
+        assert!(stdout.contains("] error\n") && stdout.contains("] info\n"));
+        assert!(!stdout.contains("] debug\n"));
+        assert_eq!(stderr.matches("] error\n").count(), 1);
+        assert!(stderr.contains("] debug\n") && !stderr.contains("] info\n"));
+    }
+
+    #[test]
+    fn test_target_file_routing() {
+        let dir = std::env::temp_dir();
+        let app = dir.join(format!("clogger_route_app_{}.log", std::process::id()));
+        let audit = dir.join(format!("clogger_route_audit_{}.log", std::process::id()));
+        let logger = CloggerBuilder::new()
+            .log_file(app.to_str().unwrap())
+            .stdout_level(LevelFilter::Off)
+            .target_file("audit::", audit.to_str().unwrap(), true)
+            // 无法打开的文件会被跳过，不影响其他输出端
+            .target_file("net::", "/nonexistent/clogger/net.log", false)
+            .build();
+
+        logger.log("audit::login", "用户已登录");
+        logger.log("app::server", "服务已启动");
+        logger.log("net::client", "连接已建立");
+
+        let (app_log, audit_log) = (
+            std::fs::read_to_string(&app).unwrap(),
+            std::fs::read_to_string(&audit).unwrap(),
+        );
+        assert!(app_log.contains("服务已启动") && !app_log.contains("用户已登录"));
+        assert!(app_log.contains("连接已建立"));
+        assert!(audit_log.contains("用户已登录") && !audit_log.contains("服务已启动"));
+        std::fs::remove_file(&app).unwrap();
+        std::fs::remove_file(&audit).unwrap();
+    }
+
+    #[test]
+    fn test_non_blocking_flush() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_non_blocking_{}.log", std::process::id()));
+        let logger = CloggerBuilder::new()
+            .stdout_level(LevelFilter::Off)
+            .log_file(path.to_str().unwrap())
+            .non_blocking(true)
+            .build();
+
+        for i in 0..200 {
+            logger.log("app", format!("第 {} 条", i));
+        }
+        // 刷新会等待后台线程写入积压的日志
+        logger.flush();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 200);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_non_blocking_sync_level_order() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_sync_order_{}.log", std::process::id()));
+        let logger = CloggerBuilder::new()
+            .stdout_level(LevelFilter::Off)
+            .log_file(path.to_str().unwrap())
+            .non_blocking(true)
+            .sync_level(Level::Error)
+            .build();
+
+        for i in 0..50 {
+            if i % 2 == 0 {
+                logger.log("app", format!("第 {} 条", i));
+            } else {
+                logger.error("app", format!("第 {} 条", i));
+            }
+        }
+        logger.flush();
+
+        // 同步与不同步的日志由同一个后台线程写入，保持输出的顺序
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let expected: Vec<String> = (0..50).map(|i| format!("第 {} 条", i)).collect();
+        let actual: Vec<&str> = contents
+            .lines()
+            .map(|line| line.rsplit("] ").next().unwrap())
+            .collect();
+        assert_eq!(actual, expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_buffered_console_capture() {
+        let path = std::env::temp_dir().join(format!(
+            "clogger_console_capture_{}.ansi",
+            std::process::id()
+        ));
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .buffered(true)
+            .console_capture(path.to_str().unwrap());
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        logger.log("app", "服务已启动");
+        crate::flush_clogger();
+        // 副本文件与终端的输出完全相同
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), stdout.contents());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_console_capture_unwritable() {
+        let stdout = MemoryWriter::default();
+        let mut builder =
+            CloggerBuilder::new().console_capture("/nonexistent/clogger/console.ansi");
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        // 无法打开副本文件时跳过该输出端，终端输出不受影响
+        logger.log("app", "服务已启动");
+        assert!(stdout.contents().contains("服务已启动"));
+    }
+
+    #[test]
+    fn test_buffered_target_file_flush() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_buffered_audit_{}.log", std::process::id()));
+        let logger = CloggerBuilder::new()
+            .stdout_level(LevelFilter::Off)
+            .buffered(true)
+            .target_file("audit::", path.to_str().unwrap(), true)
+            .build();
+
+        logger.log("audit::login", "用户已登录");
+        crate::flush_clogger();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("用户已登录"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_level_file() {
+        let dir = std::env::temp_dir();
+        let path = |level: &str| dir.join(format!("clogger_{}_{}.log", level, std::process::id()));
+        let (info, warn, error) = (path("info"), path("warn"), path("error"));
+        let logger = CloggerBuilder::new()
+            .stdout_level(LevelFilter::Off)
+            .buffered(true)
+            .level_file(Level::Info, info.to_str().unwrap())
+            .level_file(Level::Warn, warn.to_str().unwrap())
+            .level_file(Level::Error, error.to_str().unwrap())
+            // 无法打开的文件会被跳过，不影响其他输出端
+            .level_file(Level::Debug, "/nonexistent/clogger/debug.log")
+            .build();
+
+        logger.log("app", "服务已启动");
+        logger.warn("app", "磁盘空间不足");
+        logger.error("app", "无法连接到数据库");
+        logger.debug("app", "调试信息");
+        crate::flush_clogger();
+
+        // 每个文件只包含级别完全相同的日志
+        for (path, expected) in [
+            (&info, "服务已启动"),
+            (&warn, "磁盘空间不足"),
+            (&error, "无法连接到数据库"),
+        ] {
+            let contents = std::fs::read_to_string(path).unwrap();
+            assert_eq!(contents.lines().count(), 1, "{}", contents);
+            assert!(contents.contains(expected), "{}", contents);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_show_location_file() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_location_{}.log", std::process::id()));
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .log_file(path.to_str().unwrap())
+            .plain(true)
+            .show_location_file(false);
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        logger.log("app", "服务已启动");
+
+        // 终端中保留调用位置，日志文件中不显示
+        assert!(stdout.contents().contains("[app (src/builder.rs:"));
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("[app] 服务已启动"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_strip_ansi() {
+        let path = std::env::temp_dir().join(format!("clogger_ansi_{}.log", std::process::id()));
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .log_file(path.to_str().unwrap())
+            .plain(true);
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger: Clogger = builder.build();
+
+        logger.log("clogger::tests", "构建\x1b[32m成功\x1b[0m");
+
+        // 只有日志文件中的转义序列被移除
+        let file = std::fs::read_to_string(&path).unwrap();
+        assert!(file.contains("] 构建成功\n"));
+        assert!(stdout.contents().contains("] 构建\x1b[32m成功\x1b[0m\n"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_sink_matrix_routing() {
+        let stderr = MemoryWriter::default();
+        let writer = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .level(LevelFilter::Trace)
+            .stdout_level(LevelFilter::Off)
+            .sink_matrix(
+                SinkMatrix::new()
+                    .route(&[Level::Error, Level::Warn], Destination::stderr())
+                    .route_with_format(
+                        &[Level::Warn, Level::Debug],
+                        Destination::writer(writer.clone()),
+                        SinkFormat::new().separator("|").show_location(false),
+                    ),
+            );
+        builder.test_console = Some((MemoryWriter::default(), stderr.clone()));
+        let logger: Clogger = builder.build();
+
+        logger.error("clogger::tests", "error");
+        logger.warn("clogger::tests", "warn");
+        logger.log("clogger::tests", "info");
+        logger.debug("clogger::tests", "debug");
+
+        let (stderr, writer) = (stderr.contents(), writer.contents());
+        assert!(stderr.contains("] error\n") && stderr.contains("] warn\n"));
+        assert!(!stderr.contains("info") && !stderr.contains("debug"));
+        assert!(writer.contains("|W|clogger::tests|warn\n"));
+        assert!(writer.contains("|D|clogger::tests|debug\n"));
+        assert!(!writer.contains("error") && !writer.contains("info"));
+    }
+
+    #[test]
+    fn test_sink_matrix_shared_file() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_matrix_shared_{}.log", std::process::id()));
+        let logger = CloggerBuilder::new()
+            .stdout_level(LevelFilter::Off)
+            .buffered(true)
+            .sink_matrix(
+                SinkMatrix::new()
+                    .route(&[Level::Error], Destination::file(path.to_str().unwrap()))
+                    .route_with_format(
+                        &[Level::Info],
+                        Destination::file(path.to_str().unwrap()),
+                        SinkFormat::new().separator("|"),
+                    )
+                    // 无法打开的文件会被跳过，不影响其他路由
+                    .route(
+                        &[Level::Warn],
+                        Destination::file("/nonexistent/clogger/warn.log"),
+                    ),
+            )
+            .build();
+
+        logger.error("app", "第一条");
+        logger.log("app", "第二条");
+        crate::flush_clogger();
+
+        // 两条路由共享同一个缓冲区，日志按写入的顺序出现
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", contents);
+        assert!(lines[0].ends_with("第一条") && lines[1].ends_with("|第二条"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_filters() {
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .filter(Arc::new(|metadata| !metadata.target().starts_with("noisy")))
+            .filter_record(Arc::new(|record| {
+                !record.args().to_string().contains("心跳")
+            }));
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        logger.log("noisy::lib", "来自第三方库");
+        logger.log("app", "心跳");
+        logger.log("app", "正常的日志");
+
+        let stdout = stdout.contents();
+        assert!(!stdout.contains("来自第三方库") && !stdout.contains("心跳"));
+        assert!(stdout.contains("正常的日志"));
+
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .plain(true)
+            .only_targets(&["app", "lib"]);
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = builder.build();
+
+        logger.log("hyper::client (src/app.rs:1^1)", "来自依赖库");
+        logger.log("app::server (src/lib.rs:1^1)", "来自本项目");
+
+        let stdout = stdout.contents();
+        assert!(!stdout.contains("来自依赖库") && stdout.contains("来自本项目"));
+    }
+
+    #[test]
+    fn test_ordered_sinks() {
+        let path = std::env::temp_dir().join(format!("clogger_ordered_{}.log", std::process::id()));
+        let stdout = MemoryWriter::default();
+        let mut builder = CloggerBuilder::new()
+            .log_file(path.to_str().unwrap())
+            .plain(true)
+            .ordered(true);
+        builder.test_console = Some((stdout.clone(), MemoryWriter::default()));
+        let logger = Arc::new(builder.build());
+
+        let threads: Vec<_> = (0..4)
+            .map(|thread| {
+                let logger = logger.clone();
+                std::thread::spawn(move || {
+                    for i in 0..200 {
+                        logger.log(
+                            "clogger::tests",
+                            format!("线程 {} 的第 {} 条日志", thread, i),
+                        );
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let messages = |text: &str| -> Vec<String> {
+            text.lines()
+                .map(|line| line.rsplit_once("] ").unwrap().1.to_string())
+                .collect()
+        };
+        let file = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(messages(&stdout.contents()).len(), 800);
+        assert_eq!(messages(&stdout.contents()), messages(&file));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+use crate::builder::CloggerBuilder;
+use crate::toggle::is_initialized;
+use crate::verbose::VerboseGuard;
+use log::LevelFilter;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+/// 捕获到的日志，每个元素为一行（不带颜色）。
+type Lines = Arc<Mutex<Vec<String>>>;
+
+thread_local! {
+    /// 当前线程中正在捕获日志的 [`TestLogger`]。
+    static CAPTURE: RefCell<Option<Lines>> = const { RefCell::new(None) };
+}
+
+/// 将格式化后的日志交给当前线程中的 [`TestLogger`] 的输出端。
+pub(crate) struct CaptureSink;
+
+impl log::Log for CaptureSink {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        CAPTURE.with(|capture| {
+            if let Some(lines) = &*capture.borrow() {
+                lines
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(record.args().to_string());
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// 在测试中捕获全局 CLogger 输出的日志（需要启用 `test-util` 特性）。
+///
+/// 通过 [`TestLogger::capture`] 创建后，当前线程输出的所有日志（包括所有级别）都会以不带颜色的格式保存在该实例中，
+/// 实例被丢弃时停止捕获并清空，因此每个测试（`cargo test` 中每个测试运行在单独的线程中）捕获到的日志互不干扰。
+/// 其他线程（包括测试中创建的线程）输出的日志不会被捕获。
+/// 嵌套创建时，日志只会被最内层的实例捕获，该实例被丢弃后外层的实例继续捕获。
+///
+/// 若 CLogger 尚未初始化，将以不输出到终端、不写入文件的配置初始化；若已经初始化，则沿用已有的配置，并额外捕获日志。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_warn, TestLogger};
+///
+/// let logs = TestLogger::capture();
+/// c_warn!("example::moudle_name", "磁盘空间不足");
+/// assert!(logs.contains("磁盘空间不足"));
+/// ```
+pub struct TestLogger {
+    lines: Lines,
+    /// 创建之前正在捕获日志的实例，被丢弃时恢复。
+    previous: Option<Lines>,
+    _verbose: VerboseGuard,
+}
+
+impl TestLogger {
+    /// 开始捕获当前线程输出的日志。
+    pub fn capture() -> Self {
+        if !is_initialized() {
+            CloggerBuilder::new()
+                .plain(true)
+                .stdout_level(LevelFilter::Off)
+                .startup_message(None)
+                .init();
+        }
+        let lines = Lines::default();
+        let previous = CAPTURE.with(|capture| capture.replace(Some(lines.clone())));
+        TestLogger {
+            lines,
+            previous,
+            // 捕获期间不受全局级别的限制
+            _verbose: VerboseGuard::new(LevelFilter::Trace),
+        }
+    }
+
+    /// 获取目前捕获到的所有日志。
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 判断捕获到的日志中是否有包含 `text` 的日志。
+    ///
+    /// # 参数
+    /// - `text`: 需要查找的文本。
+    pub fn contains(&self, text: &str) -> bool {
+        self.lines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|line| line.contains(text))
+    }
+
+    /// 清空目前捕获到的日志。
+    pub fn clear(&self) {
+        self.lines.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl Drop for TestLogger {
+    fn drop(&mut self) {
+        CAPTURE.with(|capture| *capture.borrow_mut() = self.previous.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        {
+            let logs = TestLogger::capture();
+            crate::c_debug!("clogger::tests", "被捕获的调试输出");
+            assert!(logs.contains("[D] [clogger::tests (src/capture.rs:"));
+            assert!(logs.contains("被捕获的调试输出"));
+            // 其他线程的日志不会被捕获
+            std::thread::spawn(|| crate::c_log!("clogger::tests", "其他线程的日志"))
+                .join()
+                .unwrap();
+            assert!(!logs.contains("其他线程的日志"));
+            logs.clear();
+            assert!(logs.lines().is_empty());
+        }
+        let logs = TestLogger::capture();
+        assert!(!logs.contains("被捕获的调试输出"));
+    }
+
+    #[test]
+    fn test_nested_capture() {
+        let outer = TestLogger::capture();
+        {
+            let inner = TestLogger::capture();
+            crate::c_log!("clogger::tests", "内层捕获的日志");
+            assert!(inner.contains("内层捕获的日志"));
+        }
+        // 内层的实例被丢弃后，外层的实例继续捕获
+        crate::c_log!("clogger::tests", "外层捕获的日志");
+        assert!(outer.contains("外层捕获的日志"));
+        assert!(!outer.contains("内层捕获的日志"));
+    }
+}
@@ -0,0 +1,333 @@
+use crate::builder::CloggerBuilder;
+use crate::format::TimestampMode;
+use crate::theme::ColorTheme;
+use log::{Level, LevelFilter};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// TOML 配置文件的结构，各字段与 [`CloggerBuilder`] 的同名选项对应，未设置的字段使用构建器的默认值。
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    level: Option<LevelFilter>,
+    plain: Option<bool>,
+    app_tag: Option<String>,
+    non_blocking: Option<bool>,
+    ordered: Option<bool>,
+    sanitize_control: Option<bool>,
+    startup_message: Option<String>,
+    console_capture: Option<String>,
+    unix_socket: Option<String>,
+    sqlite: Option<String>,
+    sqlite_flat_location: Option<bool>,
+    journald: Option<String>,
+    global_fields: BTreeMap<String, String>,
+    mask_fields: Option<Vec<String>>,
+    mask_fields_case_sensitive: Option<bool>,
+    format: FormatConfig,
+    stdout: SinkConfig,
+    stderr: SinkConfig,
+    file: FileConfig,
+    target_file: Vec<TargetFileConfig>,
+    level_file: Vec<LevelFileConfig>,
+}
+
+/// `[format]` 部分。
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FormatConfig {
+    timestamp: Option<TimestampMode>,
+    target_width: Option<usize>,
+    separator: Option<String>,
+    show_crate_version: Option<bool>,
+    show_location: Option<bool>,
+    show_target: Option<bool>,
+    relative_location: Option<bool>,
+    max_record_len: Option<usize>,
+    theme: Option<ThemePreset>,
+}
+
+/// `[stdout]` 与 `[stderr]` 部分。
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct SinkConfig {
+    level: Option<LevelFilter>,
+}
+
+/// `[file]` 部分。
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    path: Option<String>,
+    level: Option<LevelFilter>,
+    buffered: Option<bool>,
+    interactive: Option<bool>,
+    strip_ansi: Option<bool>,
+    flush_interval_ms: Option<u64>,
+    sync_level: Option<Level>,
+    max_size_wrap: Option<u64>,
+    utf8_bom: Option<bool>,
+}
+
+/// `[[target_file]]` 部分。
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TargetFileConfig {
+    prefix: String,
+    path: String,
+    #[serde(default)]
+    exclusive: bool,
+}
+
+/// `[[level_file]]` 部分。
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LevelFileConfig {
+    level: Level,
+    path: String,
+}
+
+/// 内置的配色主题。
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ThemePreset {
+    Default,
+    ColorblindFriendly,
+}
+
+impl Config {
+    /// 将配置应用到构建器上。
+    fn apply(self, mut builder: CloggerBuilder) -> CloggerBuilder {
+        macro_rules! set {
+            ($value:expr, $method:ident) => {
+                if let Some(value) = $value {
+                    builder = builder.$method(value);
+                }
+            };
+        }
+        set!(self.level, level);
+        set!(self.plain, plain);
+        set!(self.app_tag.as_deref(), app_tag);
+        set!(self.non_blocking, non_blocking);
+        set!(self.ordered, ordered);
+        set!(self.sanitize_control, sanitize_control);
+        set!(self.startup_message.map(Some), startup_message);
+        set!(self.console_capture.as_deref(), console_capture);
+        #[cfg(unix)]
+        set!(self.unix_socket.as_deref(), unix_socket);
+        #[cfg(not(unix))]
+        if self.unix_socket.is_some() {
+            eprintln!("CLogger 配置中的 unix_socket 仅 Unix 平台可用，已忽略");
+        }
+        #[cfg(feature = "sqlite")]
+        set!(self.sqlite.as_deref(), sqlite);
+        #[cfg(feature = "sqlite")]
+        set!(self.sqlite_flat_location, sqlite_flat_location);
+        #[cfg(not(feature = "sqlite"))]
+        if self.sqlite.is_some() || self.sqlite_flat_location.is_some() {
+            eprintln!("CLogger 配置中的 sqlite 需要启用 `sqlite` 特性，已忽略");
+        }
+        #[cfg(all(target_os = "linux", feature = "journald"))]
+        set!(self.journald.as_deref(), journald);
+        #[cfg(not(all(target_os = "linux", feature = "journald")))]
+        if self.journald.is_some() {
+            eprintln!(
+                "CLogger 配置中的 journald 仅 Linux 平台可用且需要启用 `journald` 特性，已忽略"
+            );
+        }
+        if !self.global_fields.is_empty() {
+            let fields: Vec<(&str, &str)> = self
+                .global_fields
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect();
+            builder = builder.global_fields(&fields);
+        }
+        if let Some(names) = &self.mask_fields {
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            builder = builder.mask_fields(&names);
+        }
+        set!(self.mask_fields_case_sensitive, mask_fields_case_sensitive);
+        set!(self.format.timestamp, timestamp_mode);
+        set!(self.format.target_width, target_width);
+        set!(self.format.separator.as_deref(), separator);
+        set!(self.format.show_crate_version, show_crate_version);
+        set!(self.format.show_location, show_location);
+        set!(self.format.show_target, show_target);
+        set!(self.format.relative_location, relative_location);
+        set!(self.format.max_record_len, max_record_len);
+        set!(
+            self.format.theme.map(|theme| match theme {
+                ThemePreset::Default => ColorTheme::default(),
+                ThemePreset::ColorblindFriendly => ColorTheme::colorblind_friendly(),
+            }),
+            theme
+        );
+        set!(self.stdout.level, stdout_level);
+        set!(self.stderr.level, stderr_level);
+        set!(self.file.path.as_deref(), log_file);
+        set!(self.file.level, file_level);
+        set!(self.file.buffered, buffered);
+        set!(self.file.interactive, interactive_file);
+        set!(self.file.strip_ansi, strip_file_ansi);
+        set!(
+            self.file.flush_interval_ms.map(Duration::from_millis),
+            flush_interval
+        );
+        set!(self.file.sync_level, sync_level);
+        set!(self.file.max_size_wrap, max_file_size_wrap);
+        set!(self.file.utf8_bom, utf8_bom);
+        for route in &self.target_file {
+            builder = builder.target_file(&route.prefix, &route.path, route.exclusive);
+        }
+        for route in &self.level_file {
+            builder = builder.level_file(route.level, &route.path);
+        }
+        builder
+    }
+}
+
+/// 根据 TOML 配置创建构建器。
+fn builder_from_toml(text: &str) -> io::Result<CloggerBuilder> {
+    let config: Config =
+        toml::from_str(text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(config.apply(CloggerBuilder::new()))
+}
+
+/// 根据 TOML 配置文件初始化 CLogger（需要启用 `config` 特性）。
+///
+/// 配置文件中的选项与 [`CloggerBuilder`] 的同名方法对应，未出现的选项使用默认值，
+/// 因此无需重新编译即可调整日志的级别、输出端与格式，也可以与应用的其他配置放在同一个文件中管理。
+/// 与 [`CloggerBuilder::init`] 相同，`CLOGGER_FILE` 等环境变量的优先级高于配置文件。
+///
+/// ```toml
+/// level = "debug"              # 全局日志级别
+/// plain = false                # 纯文本模式
+/// app_tag = "v1.4.2"           # 附加在每条日志中的标签
+/// non_blocking = false         # 非阻塞写入
+/// ordered = false              # 保证所有输出端中日志的顺序一致
+/// sanitize_control = true      # 转义控制字符
+/// startup_message = "MyApp 日志系统已就绪"
+/// mask_fields = ["password", "token"]  # 需要隐藏值的键值对名称
+/// mask_fields_case_sensitive = false  # 匹配名称时是否区分大小写
+/// console_capture = "/var/log/app_console.ansi"
+/// unix_socket = "/run/logcollector.sock"  # 仅 Unix 平台
+/// sqlite = "/var/log/app.db"               # 需要启用 `sqlite` 特性
+/// sqlite_flat_location = false             # fields 列中的调用位置写为扁平的键
+/// journald = "my-service"                  # 仅 Linux 平台，需要启用 `journald` 特性
+///
+/// [format]
+/// timestamp = "rfc3339"        # "local"、"utc"、"rfc3339" 或 { custom = "%H:%M:%S" }
+/// target_width = 32
+/// separator = "|"              # 字段之间的分隔符
+/// show_crate_version = true
+/// show_location = true         # 是否显示调用位置
+/// show_target = true           # 是否显示模块名称与调用位置
+/// relative_location = false    # 调用位置是否显示为相对于 crate 根目录的路径
+/// max_record_len = 1048576      # 单条日志内容的长度上限（字节）
+/// theme = "colorblind_friendly" # "default" 或 "colorblind_friendly"
+///
+/// [global_fields]              # 附加在每条日志末尾的全局键值对，按键名排序输出
+/// service = "api"
+/// region = "cn-east"
+///
+/// [stdout]
+/// level = "info"
+///
+/// [stderr]
+/// level = "warn"
+///
+/// [file]
+/// path = "/var/log/app.log"
+/// level = "debug"
+/// buffered = true
+/// interactive = false          # 逐行刷新，便于 tail -f
+/// strip_ansi = true            # 移除日志内容中的 ANSI 转义序列
+/// flush_interval_ms = 1000
+/// sync_level = "error"
+/// max_size_wrap = 1048576
+/// utf8_bom = false             # 在新建的日志文件开头写入 UTF-8 BOM
+///
+/// [[target_file]]              # 可以出现多次
+/// prefix = "audit::"
+/// path = "/var/log/audit.log"
+/// exclusive = true
+///
+/// [[level_file]]               # 可以出现多次，只写入级别完全相同的日志
+/// level = "warn"
+/// path = "/var/log/warn.log"
+/// ```
+///
+/// # 示例
+/// ```rust
+/// use clogger::init_clogger_from_toml;
+///
+/// std::fs::write(
+///     "/tmp/clogger_example.toml",
+///     "level = \"info\"\n[file]\npath = \"/tmp/clogger_example.log\"\n",
+/// )
+/// .unwrap();
+/// init_clogger_from_toml("/tmp/clogger_example.toml").expect("无法读取 CLogger 配置");
+/// ```
+///
+/// # 参数
+/// - `path`: 配置文件的路径。
+///
+/// # 错误
+/// 无法读取配置文件时返回对应的错误；配置文件的内容不合法（如未知的选项或无法解析的日志级别）时返回
+/// [`io::ErrorKind::InvalidData`] 错误。
+pub fn init_clogger_from_toml(path: impl AsRef<Path>) -> io::Result<()> {
+    builder_from_toml(&std::fs::read_to_string(path)?)?.init();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryWriter;
+
+    #[test]
+    fn test_builder_from_toml() {
+        let stdout = MemoryWriter::default();
+        let stderr = MemoryWriter::default();
+        let mut builder = builder_from_toml(
+            r#"
+            plain = true
+            app_tag = "v1.0"
+            mask_fields = ["token"]
+
+            [global_fields]
+            service = "api"
+            region = "cn-east"
+            token = "secret"
+
+            [format]
+            timestamp = { custom = "%Y" }
+
+            [stdout]
+            level = "warn"
+            "#,
+        )
+        .unwrap();
+        builder.test_console = Some((stdout.clone(), stderr.clone()));
+        let logger = builder.build();
+
+        logger.warn("clogger::tests", "warn");
+        logger.log("clogger::tests", "info");
+        let stdout = stdout.contents();
+        assert!(stdout.contains("[v1.0] [W] [clogger::tests"));
+        assert!(stdout.ends_with("] warn region=cn-east service=api token=***\n"));
+        assert!(!stdout.contains("info"));
+    }
+
+    #[test]
+    fn test_invalid_toml() {
+        for text in ["levle = \"info\"", "level = \"loud\""] {
+            let error = builder_from_toml(text).err().unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
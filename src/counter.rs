@@ -0,0 +1,254 @@
+use crate::shutdown::shutdown;
+use crate::theme::ColorTheme;
+use crate::toggle::colors_override;
+use log::Level;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// 全局 CLogger 各级别已输出的日志条数，按 `Error`、`Warn`、`Info`、`Debug`、`Trace` 的顺序排列。
+static COUNTS: [AtomicU64; 5] = [const { AtomicU64::new(0) }; 5];
+
+/// 关闭时输出的日志统计的格式，用于 [`CloggerBuilder::print_summary_on_shutdown`](crate::CloggerBuilder::print_summary_on_shutdown)。
+pub(crate) struct Summary {
+    /// 是否使用英文且不着色的格式。
+    pub(crate) plain: bool,
+    /// 初始化时判断的是否着色，输出时优先使用 [`set_colors`](crate::set_colors) 的设置。
+    pub(crate) color: bool,
+    /// 着色时使用的配色主题。
+    pub(crate) theme: ColorTheme,
+}
+
+/// 全局 CLogger 关闭时需要输出的日志统计，输出后被清空。
+static SUMMARY: Mutex<Option<Summary>> = Mutex::new(None);
+
+/// 统计各级别日志条数后将日志交给 `sinks` 的输出管线。
+///
+/// 位于按日志内容过滤之后、合并重复日志之前，因此只统计实际输出的日志，被合并的重复日志也会逐条统计，
+/// 而合并时生成的重复次数摘要不会被统计。
+pub(crate) struct LevelCounter {
+    sinks: Box<dyn log::Log>,
+    counts: &'static [AtomicU64; 5],
+}
+
+impl LevelCounter {
+    /// 创建统计交给 `sinks` 的日志条数的输出管线。
+    pub(crate) fn new(sinks: Box<dyn log::Log>) -> Self {
+        LevelCounter {
+            sinks,
+            counts: &COUNTS,
+        }
+    }
+}
+
+impl log::Log for LevelCounter {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.sinks.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        // 被级别或过滤函数过滤掉的日志不会被统计
+        if self.sinks.enabled(record.metadata()) {
+            self.counts[record.level() as usize - 1].fetch_add(1, Ordering::Relaxed);
+            self.sinks.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.sinks.flush();
+    }
+}
+
+/// 获取全局 CLogger 自初始化以来输出的 `level` 级别的日志条数。
+///
+/// 被级别或过滤函数过滤掉的日志不会被统计，通过 [`CloggerBuilder::build`](crate::CloggerBuilder::build)
+/// 创建的独立实例输出的日志也不会被统计。启用 [`CloggerBuilder::dedupe_consecutive`](crate::CloggerBuilder::dedupe_consecutive)
+/// 时，被合并的重复日志仍会逐条统计。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_warn, init_clogger, log_count};
+/// use log::Level;
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// c_warn!("example::moudle_name", "磁盘空间不足");
+/// assert!(log_count(Level::Warn) >= 1);
+/// ```
+///
+/// # 参数
+/// - `level`: 日志级别。
+pub fn log_count(level: Level) -> u64 {
+    COUNTS[level as usize - 1].load(Ordering::Relaxed)
+}
+
+/// 根据已输出的日志计算进程的退出码。
+///
+/// 全局 CLogger 输出过 `Error` 级别的日志时返回 `1`，否则返回 `0`。适合命令行工具在 `main` 正常返回时
+/// 仍以非零的退出码通知 CI 等调用方运行中出现了错误。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_error, exit_code_from_logs, init_clogger};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// c_error!("example::moudle_name", "有一个文件处理失败");
+/// assert_eq!(exit_code_from_logs(), 1);
+/// ```
+pub fn exit_code_from_logs() -> i32 {
+    if log_count(Level::Error) > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// 若全局 CLogger 输出过 `Error` 级别的日志，关闭 CLogger 并以退出码 `1` 结束进程。
+///
+/// 结束进程前会调用 [`shutdown`](crate::shutdown)，确保所有日志都已写入；没有输出过错误日志时该函数不做任何事。
+/// 通常在 `main` 的末尾调用。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_log, exit_if_errors_logged, init_clogger};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// c_log!("example::moudle_name", "所有文件处理完成");
+/// exit_if_errors_logged();
+/// ```
+pub fn exit_if_errors_logged() {
+    let code = exit_code_from_logs();
+    if code != 0 {
+        shutdown();
+        std::process::exit(code);
+    }
+}
+
+/// 设置全局 CLogger 关闭时输出日志统计。
+pub(crate) fn set_shutdown_summary(summary: Summary) {
+    *SUMMARY.lock().unwrap_or_else(|e| e.into_inner()) = Some(summary);
+}
+
+/// 若设置了关闭时输出日志统计，将其输出到标准错误（只会输出一次）。
+pub(crate) fn print_shutdown_summary() {
+    let summary = SUMMARY.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(mut summary) = summary {
+        // 着色状态可能在运行时被修改
+        summary.color = colors_override().unwrap_or(summary.color);
+        eprintln!("{}", summary_line(&summary));
+    }
+}
+
+/// 生成 `Logged: 1203 info, 12 warn, 3 error` 形式的日志统计。
+///
+/// 着色时，数量不为零的级别会使用主题中该级别标记的样式。
+fn summary_line(summary: &Summary) -> String {
+    let labels = if summary.plain {
+        [
+            (Level::Info, "info"),
+            (Level::Warn, "warn"),
+            (Level::Error, "error"),
+        ]
+    } else {
+        [
+            (Level::Info, "条常规"),
+            (Level::Warn, "条警告"),
+            (Level::Error, "条错误"),
+        ]
+    };
+    let counts: Vec<String> = labels
+        .iter()
+        .map(|&(level, label)| {
+            let count = log_count(level);
+            let text = format!("{} {}", count, label);
+            if summary.color && count > 0 {
+                summary
+                    .theme
+                    .level(level)
+                    .marker_style
+                    .paint(&text)
+                    .into_owned()
+            } else {
+                text
+            }
+        })
+        .collect();
+    if summary.plain {
+        format!("Logged: {}", counts.join(", "))
+    } else {
+        format!("日志统计: {}", counts.join("，"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dedupe::Dedupe;
+    use crate::format::DEFAULT_MAX_RECORD_LEN;
+    use log::Log;
+
+    /// 接收所有日志但不输出的输出端。
+    struct Discard;
+
+    impl log::Log for Discard {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, _: &log::Record) {}
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_level_counter() {
+        let before = log_count(Level::Error);
+        LevelCounter::new(Box::new(Discard))
+            .log(&log::Record::builder().level(Level::Error).build());
+        assert!(log_count(Level::Error) > before);
+        assert_eq!(exit_code_from_logs(), 1);
+    }
+
+    #[test]
+    fn test_count_before_dedupe() {
+        static COUNTS: [AtomicU64; 5] = [const { AtomicU64::new(0) }; 5];
+        let counter = LevelCounter {
+            sinks: Box::new(Dedupe::new(
+                Box::new(Discard),
+                false,
+                DEFAULT_MAX_RECORD_LEN,
+            )),
+            counts: &COUNTS,
+        };
+        for _ in 0..100 {
+            counter.log(
+                &log::Record::builder()
+                    .level(Level::Error)
+                    .args(format_args!("连接失败"))
+                    .build(),
+            );
+        }
+        counter.flush();
+        // 被合并的重复日志逐条统计，重复次数的摘要不被统计
+        assert_eq!(
+            COUNTS[Level::Error as usize - 1].load(Ordering::Relaxed),
+            100
+        );
+    }
+
+    #[test]
+    fn test_summary_line() {
+        let summary = |plain, color| Summary {
+            plain,
+            color,
+            theme: ColorTheme::default(),
+        };
+        let line = summary_line(&summary(true, false));
+        assert!(
+            line.starts_with("Logged: ") && line.contains(" info, ") && line.ends_with(" error")
+        );
+        assert!(!line.contains('\x1b'));
+        assert!(summary_line(&summary(false, false)).starts_with("日志统计: "));
+        LevelCounter::new(Box::new(Discard))
+            .log(&log::Record::builder().level(Level::Error).build());
+        assert!(summary_line(&summary(false, true)).contains('\x1b'));
+    }
+}
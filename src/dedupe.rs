@@ -0,0 +1,137 @@
+use crate::format::{capped_message, is_reserved_key};
+use log::Level;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard};
+
+/// 上一条日志及其之后被合并的重复次数。
+struct Previous {
+    level: Level,
+    target: String,
+    /// 日志内容与键值对（不包含 CLogger 内部使用的键，如时间戳）。
+    content: String,
+    repeated: usize,
+}
+
+/// 将连续重复的日志合并为一条，用于 [`CloggerBuilder::dedupe_consecutive`](crate::CloggerBuilder::dedupe_consecutive)。
+///
+/// 级别、目标、日志内容与键值对均相同的日志被视为重复。重复的日志不会交给输出端，直到出现一条不同的日志、
+/// 输出管线被刷新或被释放时，才会以上一条日志的级别与目标输出一条重复次数的摘要。
+/// 刷新后重新开始比较，之后与上一条相同的日志会再次输出，而不是继续计数。
+pub(crate) struct Dedupe {
+    previous: Mutex<Option<Previous>>,
+    plain: bool,
+    max_record_len: usize,
+    sinks: Box<dyn log::Log>,
+}
+
+impl Dedupe {
+    /// 创建合并 `sinks` 中连续重复日志的输出管线，`plain` 为是否使用英文的摘要。
+    ///
+    /// 用于比较的日志内容最多保留 `max_record_len` 字节。
+    pub(crate) fn new(sinks: Box<dyn log::Log>, plain: bool, max_record_len: usize) -> Self {
+        Dedupe {
+            previous: Mutex::new(None),
+            plain,
+            max_record_len,
+            sinks,
+        }
+    }
+
+    /// 锁定上一条日志。
+    fn lock(&self) -> MutexGuard<'_, Option<Previous>> {
+        self.previous.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 若上一条日志被重复过，输出重复次数的摘要并清零。
+    fn emit_repeated(&self, previous: &mut Option<Previous>) {
+        let Some(previous) = previous.as_mut().filter(|previous| previous.repeated > 0) else {
+            return;
+        };
+        let summary = if self.plain {
+            let times = if previous.repeated == 1 {
+                "time"
+            } else {
+                "times"
+            };
+            format!("(repeated {} {})", previous.repeated, times)
+        } else {
+            format!("（重复了 {} 次）", previous.repeated)
+        };
+        self.sinks.log(
+            &log::Record::builder()
+                .level(previous.level)
+                .target(&previous.target)
+                .args(format_args!("{}", summary))
+                .build(),
+        );
+        previous.repeated = 0;
+    }
+}
+
+impl log::Log for Dedupe {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.sinks.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        // 被过滤的日志不会打断连续的重复
+        if !self.sinks.enabled(record.metadata()) {
+            return;
+        }
+        let content = content(record, self.max_record_len);
+        let mut previous = self.lock();
+        if let Some(previous) = previous.as_mut().filter(|previous| {
+            previous.level == record.level()
+                && previous.target == record.target()
+                && previous.content == content
+        }) {
+            previous.repeated += 1;
+            return;
+        }
+        self.emit_repeated(&mut previous);
+        self.sinks.log(record);
+        *previous = Some(Previous {
+            level: record.level(),
+            target: record.target().to_string(),
+            content,
+            repeated: 0,
+        });
+    }
+
+    fn flush(&self) {
+        let mut previous = self.lock();
+        self.emit_repeated(&mut previous);
+        *previous = None;
+        drop(previous);
+        self.sinks.flush();
+    }
+}
+
+impl Drop for Dedupe {
+    fn drop(&mut self) {
+        let mut previous = self.lock().take();
+        self.emit_repeated(&mut previous);
+    }
+}
+
+/// 获取用于比较是否重复的日志内容（最多 `max_record_len` 字节）与键值对。
+fn content(record: &log::Record, max_record_len: usize) -> String {
+    struct Visitor<'a>(&'a mut String);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Visitor<'_> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if !is_reserved_key(key.as_str()) {
+                let _ = write!(self.0, " {}={}", key, value);
+            }
+            Ok(())
+        }
+    }
+
+    let mut content = capped_message(record.args(), max_record_len);
+    let _ = record.key_values().visit(&mut Visitor(&mut content));
+    content
+}
@@ -0,0 +1,308 @@
+use crate::format::{capped_message, record_time, DEFAULT_MAX_RECORD_LEN, TIMESTAMP_KEY};
+use chrono::Local;
+use log::kv::{Key, Value};
+use log::{Level, LevelFilter};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// 通过 [`buffer_early_logs`] 设置为全局 `log` 日志器的缓冲日志器。
+static EARLY_BUFFER: OnceLock<&'static EarlyBuffer> = OnceLock::new();
+
+/// 缓冲的键值对的值，数字保留原来的类型，其他值保存为文本。
+enum FieldValue {
+    U64(u64),
+    I64(i64),
+    Text(String),
+}
+
+/// 初始化之前输出的一条日志。
+struct EarlyRecord {
+    level: Level,
+    target: String,
+    message: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    fields: Vec<(String, FieldValue)>,
+    /// 日志的时间戳（Unix 时间，微秒），重放时作为日志的时间。
+    timestamp: i64,
+}
+
+impl EarlyRecord {
+    /// 复制 `record` 中的内容。
+    ///
+    /// 初始化之前无法得知配置的长度上限，日志内容最多保留 [`DEFAULT_MAX_RECORD_LEN`] 字节。
+    fn capture(record: &log::Record) -> Self {
+        struct Visitor<'a>(&'a mut Vec<(String, FieldValue)>);
+
+        impl<'kvs> log::kv::VisitSource<'kvs> for Visitor<'_> {
+            fn visit_pair(
+                &mut self,
+                key: Key<'kvs>,
+                value: Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                if key.as_str() == TIMESTAMP_KEY {
+                    return Ok(());
+                }
+                let value = match (value.to_u64(), value.to_i64()) {
+                    (Some(value), _) => FieldValue::U64(value),
+                    (None, Some(value)) => FieldValue::I64(value),
+                    (None, None) => FieldValue::Text(value.to_string()),
+                };
+                self.0.push((key.as_str().to_string(), value));
+                Ok(())
+            }
+        }
+
+        let mut fields = Vec::new();
+        let _ = record.key_values().visit(&mut Visitor(&mut fields));
+        EarlyRecord {
+            level: record.level(),
+            target: record.target().to_string(),
+            message: capped_message(record.args(), DEFAULT_MAX_RECORD_LEN),
+            module_path: record.module_path().map(str::to_string),
+            file: record.file().map(str::to_string),
+            line: record.line(),
+            fields,
+            timestamp: record_time(record)
+                .unwrap_or_else(Local::now)
+                .timestamp_micros(),
+        }
+    }
+
+    /// 将日志交给 `logger`，日志的时间为最初输出的时间。
+    fn replay(&self, logger: &dyn log::Log) {
+        logger.log(
+            &log::Record::builder()
+                .level(self.level)
+                .target(&self.target)
+                .module_path(self.module_path.as_deref())
+                .file(self.file.as_deref())
+                .line(self.line)
+                .key_values(self)
+                .args(format_args!("{}", self.message))
+                .build(),
+        );
+    }
+}
+
+impl log::kv::Source for EarlyRecord {
+    fn visit<'kvs>(
+        &'kvs self,
+        visitor: &mut dyn log::kv::VisitSource<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        for (key, value) in &self.fields {
+            let value = match value {
+                FieldValue::U64(value) => Value::from(*value),
+                FieldValue::I64(value) => Value::from(*value),
+                FieldValue::Text(value) => Value::from(value.as_str()),
+            };
+            visitor.visit_pair(Key::from_str(key), value)?;
+        }
+        visitor.visit_pair(Key::from_str(TIMESTAMP_KEY), Value::from(self.timestamp))
+    }
+}
+
+/// 缓冲的日志及因超出容量而被丢弃的条数。
+#[derive(Default)]
+struct Queue {
+    records: Vec<EarlyRecord>,
+    dropped: usize,
+}
+
+/// 在 CLogger 初始化之前缓冲日志，初始化后将日志转发给 CLogger。
+struct EarlyBuffer {
+    capacity: usize,
+    queue: Mutex<Queue>,
+    target: OnceLock<Box<dyn log::Log>>,
+}
+
+impl EarlyBuffer {
+    /// 创建最多缓冲 `capacity` 条日志的缓冲日志器。
+    fn new(capacity: usize) -> Self {
+        EarlyBuffer {
+            capacity,
+            queue: Mutex::new(Queue::default()),
+            target: OnceLock::new(),
+        }
+    }
+
+    /// 锁定缓冲的日志。
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 之后的日志转发给 `logger`，并按顺序重放缓冲的日志，`plain` 为是否使用英文的丢弃提示。
+    fn attach(&self, logger: Box<dyn log::Log>, plain: bool) {
+        // 持有锁期间设置转发目标，等待中的日志在锁释放后会直接转发，不会被遗漏
+        let mut queue = self.lock();
+        let logger = match self.target.set(logger) {
+            Ok(()) => self.target.get().unwrap(),
+            Err(_) => return,
+        };
+        for record in queue.records.drain(..) {
+            record.replay(logger.as_ref());
+        }
+        if queue.dropped > 0 {
+            let message = if plain {
+                format!(
+                    "{} log records before initialization were dropped because the buffer was full",
+                    queue.dropped
+                )
+            } else {
+                format!(
+                    "初始化之前的日志超出了缓冲区的容量，丢弃了 {} 条",
+                    queue.dropped
+                )
+            };
+            logger.log(
+                &log::Record::builder()
+                    .level(Level::Warn)
+                    .target(module_path!())
+                    .args(format_args!("{}", message))
+                    .build(),
+            );
+        }
+    }
+}
+
+impl log::Log for EarlyBuffer {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        match self.target.get() {
+            Some(logger) => logger.enabled(metadata),
+            None => true,
+        }
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Some(logger) = self.target.get() {
+            return logger.log(record);
+        }
+        let mut queue = self.lock();
+        match self.target.get() {
+            Some(logger) => {
+                drop(queue);
+                logger.log(record);
+            }
+            // 保留最早的日志，启动阶段最开始的日志通常最重要
+            None if queue.records.len() >= self.capacity => queue.dropped += 1,
+            None => queue.records.push(EarlyRecord::capture(record)),
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(logger) = self.target.get() {
+            logger.flush();
+        }
+    }
+}
+
+/// 在 CLogger 初始化之前缓冲日志，初始化后按顺序重放，避免程序启动阶段的日志丢失。
+///
+/// `log` crate 在没有设置日志器时会直接丢弃日志，因此在 [`init_clogger`](crate::init_clogger) 之前通过 `log` 宏
+/// （包括第三方库）输出的日志默认会丢失。在 `main` 函数的开头调用该函数后，这些日志会被保存在内存中（最多 `capacity` 条），
+/// 并在 CLogger 初始化后以最初输出的时间写入各个输出端，同样遵循 CLogger 的级别与过滤设置。
+/// 超出容量时保留最早的日志，重放结束后会输出一条警告说明丢弃的条数。
+///
+/// 缓冲期间所有级别的日志都会被保存，因此 `capacity` 应按启动阶段的日志量设置。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{buffer_early_logs, init_clogger};
+///
+/// buffer_early_logs(1024).unwrap();
+/// log::info!("读取配置文件"); // 暂时保存在内存中
+/// init_clogger("/tmp/clogger_example.log"); // 初始化后输出上面的日志
+/// ```
+///
+/// # 参数
+/// - `capacity`: 最多缓冲的日志条数。
+///
+/// # 错误
+/// 已经设置了其他的 `log` 日志器（包括已经初始化了 CLogger 或重复调用该函数）时返回错误。
+pub fn buffer_early_logs(capacity: usize) -> Result<(), log::SetLoggerError> {
+    let buffer: &'static EarlyBuffer = Box::leak(Box::new(EarlyBuffer::new(capacity)));
+    log::set_logger(buffer)?;
+    // `log` 的日志器只能设置一次，因此这里总是成功
+    let _ = EARLY_BUFFER.set(buffer);
+    log::set_max_level(LevelFilter::Trace);
+    Ok(())
+}
+
+/// 将 `logger` 设置为全局的 `log` 日志器。
+///
+/// 已经通过 [`buffer_early_logs`] 安装了缓冲日志器时，由缓冲日志器转发日志，并重放缓冲的日志。
+pub(crate) fn set_global_logger(logger: Box<dyn log::Log>, plain: bool) {
+    match EARLY_BUFFER.get() {
+        Some(buffer) => buffer.attach(logger, plain),
+        None => log::set_boxed_logger(logger).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Log;
+    use std::sync::Arc;
+
+    /// 收到的日志内容与时间戳。
+    type Received = Vec<(String, Option<i64>)>;
+
+    /// 记录收到的日志内容与时间戳。
+    #[derive(Clone, Default)]
+    struct Sink(Arc<Mutex<Received>>);
+
+    impl log::Log for Sink {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let time = record_time(record).map(|time| time.timestamp_micros());
+            self.0
+                .lock()
+                .unwrap()
+                .push((record.args().to_string(), time));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_replay() {
+        let buffer = EarlyBuffer::new(2);
+        for message in ["读取配置", "连接数据库", "超出容量"] {
+            buffer.log(
+                &log::Record::builder()
+                    .level(Level::Info)
+                    .target("app")
+                    .args(format_args!("{}", message))
+                    .build(),
+            );
+        }
+
+        let sink = Sink::default();
+        buffer.attach(Box::new(sink.clone()), false);
+        buffer.log(
+            &log::Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("初始化之后"))
+                .build(),
+        );
+
+        let logs = sink.0.lock().unwrap();
+        let messages: Vec<&str> = logs.iter().map(|(message, _)| message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "读取配置",
+                "连接数据库",
+                "初始化之前的日志超出了缓冲区的容量，丢弃了 1 条",
+                "初始化之后"
+            ]
+        );
+        // 重放的日志保留最初输出的时间
+        assert!(logs[0].1.is_some() && logs[0].1 <= logs[1].1);
+        assert_eq!(logs[3].1, None);
+    }
+}
@@ -0,0 +1,79 @@
+use std::io;
+use std::ptr;
+use windows_sys::Win32::Foundation::HANDLE;
+use windows_sys::Win32::System::EventLog::{
+    DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+    EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, REPORT_EVENT_TYPE,
+};
+
+/// 将日志写入 Windows 事件日志的输出端。
+pub(crate) struct EventLogSink {
+    handle: HANDLE,
+}
+
+// 事件源句柄可以在多个线程中同时使用
+unsafe impl Send for EventLogSink {}
+unsafe impl Sync for EventLogSink {}
+
+impl EventLogSink {
+    /// 打开名为 `source` 的事件源。
+    pub(crate) fn open(source: &str) -> io::Result<Self> {
+        let source = to_wide(source);
+        // SAFETY: `source` 是以 NUL 结尾的 UTF-16 字符串，在调用期间保持有效
+        let handle = unsafe { RegisterEventSourceW(ptr::null(), source.as_ptr()) };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(EventLogSink { handle })
+    }
+}
+
+impl Drop for EventLogSink {
+    fn drop(&mut self) {
+        // SAFETY: `handle` 由 `RegisterEventSourceW` 返回，且只会被释放一次
+        unsafe {
+            DeregisterEventSource(self.handle);
+        }
+    }
+}
+
+impl log::Log for EventLogSink {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let message = to_wide(&format!("[{}] {}", record.target(), record.args()));
+        let strings = [message.as_ptr()];
+        // SAFETY: `strings` 中的字符串以 NUL 结尾，在调用期间保持有效
+        unsafe {
+            ReportEventW(
+                self.handle,
+                event_type(record.level()),
+                0,
+                0,
+                ptr::null_mut(),
+                strings.len() as u16,
+                0,
+                strings.as_ptr(),
+                ptr::null(),
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// 将日志级别映射为事件日志的事件类型，`Debug` 与 `Trace` 记录为信息事件。
+fn event_type(level: log::Level) -> REPORT_EVENT_TYPE {
+    match level {
+        log::Level::Error => EVENTLOG_ERROR_TYPE,
+        log::Level::Warn => EVENTLOG_WARNING_TYPE,
+        log::Level::Info | log::Level::Debug | log::Level::Trace => EVENTLOG_INFORMATION_TYPE,
+    }
+}
+
+/// 将文本转换为以 NUL 结尾的 UTF-16 字符串。
+fn to_wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
@@ -0,0 +1,20 @@
+use std::fmt;
+
+/// 可以被 [`c_expect!`](crate::c_expect) 取出值的类型，即 `Option<T>` 与 `Result<T, E>`。
+#[doc(hidden)]
+pub trait Expect<T> {
+    /// 取出成功时的值；失败时返回错误的描述，`None` 没有错误的描述。
+    fn into_expected(self) -> Result<T, Option<String>>;
+}
+
+impl<T> Expect<T> for Option<T> {
+    fn into_expected(self) -> Result<T, Option<String>> {
+        self.ok_or(None)
+    }
+}
+
+impl<T, E: fmt::Debug> Expect<T> for Result<T, E> {
+    fn into_expected(self) -> Result<T, Option<String>> {
+        self.map_err(|error| Some(format!("{:?}", error)))
+    }
+}
@@ -0,0 +1,764 @@
+use crate::meta::write_format_change;
+use crate::shutdown::spawn_worker;
+use crate::worker::{spawn_line_writer, WorkerWriter};
+use log::Level;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, Weak};
+use std::time::Duration;
+
+/// 当前正在使用的日志文件。
+pub(crate) static ACTIVE_FILE: OnceLock<Arc<LogFile>> = OnceLock::new();
+
+/// CLogger 打开的所有日志文件（包括按模块或级别路由的文件），供 [`flush_clogger`] 刷新。
+static OPEN_FILES: Mutex<Vec<Weak<LogFile>>> = Mutex::new(Vec::new());
+
+/// 日志文件的写入选项。
+#[derive(Clone, Copy, Default)]
+pub(crate) struct FileOptions {
+    /// 是否缓冲写入，启用后只有在缓冲区已满或被显式刷新时才会写入磁盘。
+    pub(crate) buffered: bool,
+    /// 是否在每条日志写入后立即刷新，启用后 `buffered` 不再生效，便于 `tail -f` 实时查看。
+    pub(crate) interactive: bool,
+    /// 回绕写入的文件大小上限（字节），达到上限后从文件开头（头部标记之后）覆盖旧的日志。
+    pub(crate) wrap_size: Option<u64>,
+    /// 是否在新建（或为空）的日志文件开头写入 UTF-8 BOM，回绕模式下不生效。
+    pub(crate) utf8_bom: bool,
+}
+
+/// UTF-8 编码的字节顺序标记（BOM）。
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// 回绕模式下文件开头的头部标记，记录下一条日志的写入位置（即最旧日志的起始位置）。
+const WRAP_HEADER_PREFIX: &str = "CLOGGER-WRAP ";
+
+/// 回绕模式下头部标记的长度（字节）。
+const WRAP_HEADER_LEN: u64 = WRAP_HEADER_PREFIX.len() as u64 + 20 + 1;
+
+/// 生成记录写入位置 `offset` 的头部标记。
+fn wrap_header(offset: u64) -> String {
+    format!("{}{:020}\n", WRAP_HEADER_PREFIX, offset)
+}
+
+/// 可重新打开的日志文件。
+///
+/// 文件句柄保存在 `Mutex` 中，以便在外部工具（如 `logrotate`）移动日志文件后重新打开同一路径，
+/// 或在运行时切换到新的路径。
+pub(crate) struct LogFile {
+    /// 日志文件的路径，使用预先打开的文件时为 `None`。
+    path: Mutex<Option<PathBuf>>,
+    options: FileOptions,
+    writer: Mutex<BufWriter<File>>,
+    /// 当前文件的文件描述符，供信号处理函数在不加锁的情况下写入。
+    #[cfg(unix)]
+    fd: AtomicI32,
+}
+
+impl LogFile {
+    /// 以追加模式打开 `path` 处的日志文件，文件不存在时将自动创建。
+    ///
+    /// 回绕模式下会从头部标记记录的位置继续写入。
+    pub(crate) fn open(path: &Path, options: FileOptions) -> io::Result<Self> {
+        let file = open_file(path, options)?;
+        Ok(LogFile::new(Some(path.to_path_buf()), options, file))
+    }
+
+    /// 使用预先打开的文件，日志从文件末尾开始写入（无法定位的文件，如管道，从当前位置写入）。
+    ///
+    /// 这类文件没有路径，因此无法被重新打开。
+    pub(crate) fn from_file(file: File, options: FileOptions) -> io::Result<Self> {
+        let file = prepare_file(file, options)?;
+        Ok(LogFile::new(None, options, file))
+    }
+
+    /// 创建写入 `file` 的日志文件。
+    fn new(path: Option<PathBuf>, options: FileOptions, file: File) -> Self {
+        LogFile {
+            path: Mutex::new(path),
+            options,
+            #[cfg(unix)]
+            fd: AtomicI32::new(file.as_raw_fd()),
+            writer: Mutex::new(BufWriter::new(file)),
+        }
+    }
+
+    /// 获取日志文件的路径，使用预先打开的文件时为 `None`。
+    pub(crate) fn path(&self) -> Option<PathBuf> {
+        self.path.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 获取当前文件的文件描述符。
+    #[cfg(unix)]
+    pub(crate) fn raw_fd(&self) -> RawFd {
+        self.fd.load(Ordering::Acquire)
+    }
+
+    /// 使用 `file` 替换当前文件，替换前会刷新当前文件的缓冲区。
+    fn replace(&self, writer: &mut BufWriter<File>, file: File) -> io::Result<()> {
+        writer.flush()?;
+        // 先更新文件描述符再关闭旧文件，信号处理函数不会写入已经关闭的文件描述符
+        #[cfg(unix)]
+        self.fd.store(file.as_raw_fd(), Ordering::Release);
+        *writer = BufWriter::new(file);
+        Ok(())
+    }
+
+    /// 获取当前日志文件的大小（字节），包含尚未写入磁盘的缓冲内容。
+    pub(crate) fn size(&self) -> io::Result<u64> {
+        let writer = self.lock();
+        Ok(writer.get_ref().metadata()?.len() + writer.buffer().len() as u64)
+    }
+
+    /// 将缓冲区中的内容写入磁盘。
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        self.lock().flush()
+    }
+
+    /// 将缓冲区中的内容写入磁盘，并等待操作系统将文件数据同步到存储设备。
+    pub(crate) fn sync(&self) -> io::Result<()> {
+        let mut writer = self.lock();
+        writer.flush()?;
+        writer.get_ref().sync_data()
+    }
+
+    /// 刷新当前文件后重新打开日志文件的路径，使用预先打开的文件时返回 [`io::ErrorKind::Unsupported`] 错误。
+    #[cfg(any(unix, test))]
+    pub(crate) fn reopen(&self) -> io::Result<()> {
+        let path = self.path().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "预先打开的日志文件无法被重新打开",
+            )
+        })?;
+        let mut writer = self.lock();
+        let file = open_file(&path, self.options)?;
+        self.replace(&mut writer, file)
+    }
+
+    /// 刷新并关闭当前文件，之后的日志写入 `path` 处的日志文件。
+    ///
+    /// 新文件打开失败时仍继续写入当前文件。
+    pub(crate) fn switch(&self, path: &Path) -> io::Result<()> {
+        let file = open_file(path, self.options)?;
+        let mut writer = self.lock();
+        self.replace(&mut writer, file)?;
+        *self.path.lock().unwrap_or_else(|e| e.into_inner()) = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// 以回绕的方式写入一条完整的日志，并更新头部标记中的写入位置。
+    fn write_wrapped(&self, record: &[u8], wrap_size: u64) -> io::Result<()> {
+        let mut writer = self.lock();
+        let mut offset = writer.stream_position()?;
+        if offset > WRAP_HEADER_LEN && offset + record.len() as u64 > wrap_size {
+            offset = writer.seek(SeekFrom::Start(WRAP_HEADER_LEN))?;
+        }
+        writer.write_all(record)?;
+        writer.seek(SeekFrom::Start(0))?;
+        writer.write_all(wrap_header(offset + record.len() as u64).as_bytes())?;
+        writer.seek(SeekFrom::Start(offset + record.len() as u64))?;
+        Ok(())
+    }
+
+    /// 锁定文件的写入端。
+    fn lock(&self) -> MutexGuard<'_, BufWriter<File>> {
+        self.writer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 按照写入选项打开文件。
+///
+/// 通常以追加模式打开；回绕模式下需要在文件中移动写入位置，因此以读写模式打开。
+fn open_file(path: &Path, options: FileOptions) -> io::Result<File> {
+    let file = if options.wrap_size.is_none() {
+        OpenOptions::new().create(true).append(true).open(path)?
+    } else {
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(path)?
+    };
+    prepare_file(file, options)
+}
+
+/// 按照写入选项准备开始写入已打开的文件。
+///
+/// 通常定位到文件末尾，需要时在空文件的开头写入 UTF-8 BOM，已有内容的文件不会被写入 BOM；
+/// 回绕模式下定位到头部标记记录的位置（文件为空或没有头部标记时写入新的头部标记）。
+fn prepare_file(mut file: File, options: FileOptions) -> io::Result<File> {
+    if options.wrap_size.is_none() {
+        // 管道等无法定位的文件从当前位置写入
+        let _ = file.seek(SeekFrom::End(0));
+        if options.utf8_bom && file.metadata()?.len() == 0 {
+            file.write_all(UTF8_BOM)?;
+        }
+        return Ok(file);
+    }
+    file.seek(SeekFrom::Start(0))?;
+    let mut header = String::new();
+    BufReader::new(&file).read_line(&mut header)?;
+    let offset = header
+        .strip_prefix(WRAP_HEADER_PREFIX)
+        .and_then(|offset| offset.trim_end().parse().ok())
+        .filter(|&offset| offset >= WRAP_HEADER_LEN);
+    match offset {
+        Some(offset) => {
+            file.seek(SeekFrom::Start(offset))?;
+        }
+        None => {
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(wrap_header(WRAP_HEADER_LEN).as_bytes())?;
+        }
+    }
+    Ok(file)
+}
+
+/// 供 fern 写入 [`LogFile`] 的输出端。
+///
+/// fern 会在每条日志写入后调用 `flush`，未启用缓冲时会立即写入磁盘，否则交由缓冲区决定写入时机。
+/// 回绕模式下每条日志会先暂存在写入端中，在 `flush` 时作为一个整体写入，避免同一条日志被回绕拆开。
+pub(crate) struct LogFileWriter {
+    file: Arc<LogFile>,
+    pending: Vec<u8>,
+}
+
+impl LogFileWriter {
+    /// 创建一个写入 `file` 的输出端。
+    pub(crate) fn new(file: Arc<LogFile>) -> Self {
+        LogFileWriter {
+            file,
+            pending: Vec::new(),
+        }
+    }
+
+    /// 写入一条完整的日志，`sync` 为写入后是否同步到存储设备。
+    ///
+    /// 同步时无论是否启用缓冲，都会将缓冲区（包括之前的日志）写入磁盘并调用 [`File::sync_data`]。
+    pub(crate) fn write_record(&mut self, line: &[u8], sync: bool) -> io::Result<()> {
+        self.write_all(line)?;
+        self.finish(sync)
+    }
+
+    /// 写入暂存的日志，并按照写入选项刷新或同步缓冲区。
+    fn finish(&mut self, sync: bool) -> io::Result<()> {
+        if let Some(wrap_size) = self.file.options.wrap_size {
+            let result = self.file.write_wrapped(&self.pending, wrap_size);
+            self.pending.clear();
+            result?;
+        }
+        if sync {
+            self.file.sync()
+        } else if self.file.options.buffered && !self.file.options.interactive {
+            Ok(())
+        } else {
+            self.file.flush()
+        }
+    }
+}
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.file.options.wrap_size.is_some() {
+            self.pending.extend_from_slice(buf);
+            return Ok(buf.len());
+        }
+        self.file.lock().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.finish(false)
+    }
+}
+
+/// 写入日志文件的 fern 输出端，达到同步级别的日志写入后会同步到存储设备。
+///
+/// 每个文件只使用一个写入端（启用非阻塞写入时只使用一个后台线程），是否同步按每条日志的级别决定，
+/// 因此同一线程输出的日志总是按照输出的顺序写入文件。
+pub(crate) struct FileOutput {
+    sync_level: Option<Level>,
+    writer: FileOutputWriter,
+}
+
+/// [`FileOutput`] 的写入方式。
+enum FileOutputWriter {
+    /// 在记录日志的线程中直接写入。
+    Direct(Mutex<LogFileWriter>),
+    /// 交由后台线程写入。
+    Worker(WorkerWriter),
+}
+
+impl FileOutput {
+    /// 创建直接写入 `file` 的输出端，`sync_level` 及以上级别的日志写入后会同步到存储设备。
+    pub(crate) fn new(file: Arc<LogFile>, sync_level: Option<Level>) -> Self {
+        FileOutput {
+            sync_level,
+            writer: FileOutputWriter::Direct(Mutex::new(LogFileWriter::new(file))),
+        }
+    }
+
+    /// 创建交由名为 `name` 的后台线程写入 `file` 的输出端。
+    pub(crate) fn non_blocking(name: &str, file: Arc<LogFile>, sync_level: Option<Level>) -> Self {
+        let mut writer = LogFileWriter::new(file);
+        let worker = spawn_line_writer(name, move |line, sync| {
+            let _ = writer.write_record(line, sync);
+        });
+        FileOutput {
+            sync_level,
+            writer: FileOutputWriter::Worker(worker),
+        }
+    }
+}
+
+impl log::Log for FileOutput {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let sync = self
+            .sync_level
+            .is_some_and(|sync_level| record.level() <= sync_level);
+        self.write_line(format!("{}\n", record.args()), sync);
+    }
+
+    fn flush(&self) {
+        match &self.writer {
+            FileOutputWriter::Direct(writer) => {
+                let _ = writer.lock().unwrap_or_else(|e| e.into_inner()).flush();
+            }
+            FileOutputWriter::Worker(worker) => worker.drain(),
+        }
+    }
+}
+
+impl FileOutput {
+    /// 写入一行完整的内容（包含换行符），`sync` 为写入后是否同步到存储设备。
+    ///
+    /// 与日志使用同一个写入端，启用非阻塞写入时排在之前发送的日志之后。
+    pub(crate) fn write_line(&self, line: String, sync: bool) {
+        match &self.writer {
+            FileOutputWriter::Direct(writer) => {
+                let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+                if let Err(e) = writer.write_record(line.as_bytes(), sync) {
+                    eprintln!("CLogger 写入日志文件时出现错误: {}", e);
+                }
+            }
+            FileOutputWriter::Worker(worker) => worker.send_line(line.into_bytes(), sync),
+        }
+    }
+}
+
+/// 启动一个后台线程，每隔 `interval` 刷新一次日志文件，日志文件被释放或 CLogger 被关闭后线程将自动退出。
+pub(crate) fn spawn_flusher(file: &Arc<LogFile>, interval: Duration) {
+    let file = Arc::downgrade(file);
+    spawn_worker("flush", move |stop| {
+        while !stop.wait(interval) {
+            match file.upgrade() {
+                Some(file) => {
+                    let _ = file.flush();
+                }
+                None => return,
+            }
+        }
+        if let Some(file) = file.upgrade() {
+            let _ = file.flush();
+        }
+    });
+}
+
+/// 记录 CLogger 打开的日志文件，使 [`flush_clogger`] 可以刷新该文件。
+pub(crate) fn register_file(file: &Arc<LogFile>) {
+    let mut files = OPEN_FILES.lock().unwrap_or_else(|e| e.into_inner());
+    // 顺便清理已经被释放的文件（如被释放的 Clogger 实例的文件）
+    files.retain(|file| file.strong_count() > 0);
+    files.push(Arc::downgrade(file));
+}
+
+/// 获取 CLogger 打开的所有仍在使用的日志文件。
+fn open_files() -> Vec<Arc<LogFile>> {
+    OPEN_FILES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect()
+}
+
+/// 重新打开 CLogger 打开的所有日志文件，预先打开的文件（没有路径）会被跳过。
+#[cfg(unix)]
+fn reopen_files() {
+    for file in open_files() {
+        let Some(path) = file.path() else {
+            continue;
+        };
+        if let Err(e) = file.reopen() {
+            eprintln!(
+                "CLogger 重新打开日志文件 {} 时出现错误: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// 立即刷新 CLogger 的所有输出端。
+///
+/// 启用缓冲写入（[`CloggerBuilder::buffered`](crate::CloggerBuilder::buffered)）后，日志会先保存在缓冲区中，
+/// 可以在程序退出前或其他关键时刻调用该函数，确保所有日志都已写入磁盘。
+/// 启用非阻塞写入（[`CloggerBuilder::non_blocking`](crate::CloggerBuilder::non_blocking)）时，
+/// 该函数会等待后台线程写入所有积压的日志（每个输出端最多等待 5 秒）后再返回。
+/// 刷新的范围包括 CLogger 打开的所有日志文件，如 [`CloggerBuilder::target_file`](crate::CloggerBuilder::target_file)
+/// 路由的文件以及通过 [`CloggerBuilder::build`](crate::CloggerBuilder::build) 创建的独立实例的文件。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_log, flush_clogger, CloggerBuilder};
+///
+/// CloggerBuilder::new()
+///     .log_file("/tmp/clogger_example.log")
+///     .buffered(true)
+///     .init();
+/// c_log!("example::moudle_name", "这是一条常规日志！(づ｡◕‿‿◕｡)づ");
+/// flush_clogger(); // 确保日志已写入磁盘
+/// ```
+pub fn flush_clogger() {
+    log::logger().flush();
+    for file in open_files() {
+        if let Err(e) = file.flush() {
+            eprintln!("CLogger 刷新日志文件时出现错误: {}", e);
+        }
+    }
+}
+
+/// 获取 CLogger 当前正在写入的日志文件的路径。
+///
+/// 若 CLogger 尚未初始化、没有配置日志文件或使用的是预先打开的文件（见 [`init_clogger_with_file`](crate::init_clogger_with_file)），将返回 `None`。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{current_log_path, init_clogger};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// assert_eq!(
+///     current_log_path(),
+///     Some(std::path::PathBuf::from("/tmp/clogger_example.log"))
+/// );
+/// ```
+pub fn current_log_path() -> Option<PathBuf> {
+    ACTIVE_FILE.get().and_then(|file| file.path())
+}
+
+/// 获取 CLogger 当前正在写入的日志文件的大小（字节）。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{current_log_size, init_clogger};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// println!("日志文件大小: {} 字节", current_log_size().unwrap());
+/// ```
+///
+/// # 错误
+/// 若 CLogger 尚未初始化或没有配置日志文件，将返回 [`io::ErrorKind::NotFound`] 错误；
+/// 读取文件信息失败时返回对应的错误。
+pub fn current_log_size() -> io::Result<u64> {
+    match ACTIVE_FILE.get() {
+        Some(file) => file.size(),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "CLogger 没有配置日志文件",
+        )),
+    }
+}
+
+/// 在运行时将 CLogger 的日志文件切换到 `path`，无需重新初始化。
+///
+/// 该函数会刷新并关闭当前的日志文件，之后的日志写入新的文件（文件不存在时将自动创建），
+/// 日志级别、格式与终端输出等其他配置保持不变，适合让用户在运行时选择日志目录的桌面应用。
+/// 按模块路由的日志文件（[`CloggerBuilder::target_file`](crate::CloggerBuilder::target_file)）不受影响。
+/// 切换后会在新的日志文件中写入一行 JSON 元数据（见 [`set_level`](crate::set_level)），说明日志文件在运行时发生了切换。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_log, current_log_path, init_clogger, set_log_file};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// // 用户选择了新的日志目录
+/// set_log_file("/tmp/clogger_example_moved.log").expect("无法打开新的日志文件");
+/// c_log!("example::moudle_name", "这条日志会写入新的日志文件");
+/// assert_eq!(
+///     current_log_path(),
+///     Some(std::path::PathBuf::from("/tmp/clogger_example_moved.log"))
+/// );
+/// ```
+///
+/// # 错误
+/// 若 CLogger 尚未初始化或没有配置日志文件，将返回 [`io::ErrorKind::NotFound`] 错误；
+/// 新的日志文件打开失败时返回对应的错误，此时日志仍写入原来的文件。
+pub fn set_log_file(path: impl AsRef<Path>) -> io::Result<()> {
+    match ACTIVE_FILE.get() {
+        Some(file) => {
+            file.switch(path.as_ref())?;
+            write_format_change("log_file");
+            Ok(())
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "CLogger 没有配置日志文件",
+        )),
+    }
+}
+
+/// 在收到 `SIGHUP` 信号时重新打开日志文件（仅 Unix 平台可用）。
+///
+/// 该函数会启动一个后台线程监听 `SIGHUP`，收到信号后将刷新并重新打开 CLogger 打开的所有日志文件
+/// （包括按模块或级别路由的文件、路由表中的文件与终端输出的副本文件），
+/// 使 CLogger 能够配合系统的 `logrotate` 工作，而不会在日志文件被移动后继续写入旧文件。
+/// 该函数可以在 [`init_clogger`](crate::init_clogger) 之前或之后调用。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, install_sighup_reopen};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// install_sighup_reopen().expect("无法监听 SIGHUP 信号");
+/// ```
+///
+/// # 错误
+/// 无法注册信号处理函数时返回错误。
+#[cfg(unix)]
+pub fn install_sighup_reopen() -> io::Result<()> {
+    use signal_hook::consts::SIGHUP;
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGHUP])?;
+    std::thread::Builder::new()
+        .name("clogger-sighup".to_string())
+        .spawn(move || {
+            for _ in signals.forever() {
+                reopen_files();
+            }
+        })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reopen_registered_files() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("clogger_reopen_routed_{}.log", std::process::id()));
+        let rotated = dir.join(format!(
+            "clogger_reopen_routed_{}.log.1",
+            std::process::id()
+        ));
+
+        let file = Arc::new(LogFile::open(&path, FileOptions::default()).unwrap());
+        register_file(&file);
+        let mut writer = LogFileWriter::new(file);
+        writeln!(writer, "轮转前").unwrap();
+        std::fs::rename(&path, &rotated).unwrap();
+        // 所有登记过的日志文件都会被重新打开，而不只是全局的日志文件
+        reopen_files();
+        writeln!(writer, "轮转后").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(std::fs::read_to_string(&rotated).unwrap(), "轮转前\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "轮转后\n");
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+
+    #[test]
+    fn test_reopen_after_rotate() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("clogger_reopen_{}.log", std::process::id()));
+        let rotated = dir.join(format!("clogger_reopen_{}.log.1", std::process::id()));
+
+        let mut writer = LogFileWriter::new(Arc::new(
+            LogFile::open(&path, FileOptions::default()).unwrap(),
+        ));
+        writeln!(writer, "轮转前").unwrap();
+        std::fs::rename(&path, &rotated).unwrap();
+        writer.file.reopen().unwrap();
+        writeln!(writer, "轮转后").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(std::fs::read_to_string(&rotated).unwrap(), "轮转前\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "轮转后\n");
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+
+    #[test]
+    fn test_switch() {
+        let dir = std::env::temp_dir();
+        let old = dir.join(format!("clogger_switch_old_{}.log", std::process::id()));
+        let new = dir.join(format!("clogger_switch_new_{}.log", std::process::id()));
+
+        let options = FileOptions {
+            buffered: true,
+            ..Default::default()
+        };
+        let mut writer = LogFileWriter::new(Arc::new(LogFile::open(&old, options).unwrap()));
+        writeln!(writer, "切换前").unwrap();
+        // 无法打开的路径不影响当前文件
+        assert!(writer.file.switch(&dir.join("不存在的目录/x.log")).is_err());
+        writer.file.switch(&new).unwrap();
+        assert_eq!(writer.file.path(), Some(new.clone()));
+        writeln!(writer, "切换后").unwrap();
+        writer.file.flush().unwrap();
+
+        // 切换时会刷新旧文件中缓冲的日志
+        assert_eq!(std::fs::read_to_string(&old).unwrap(), "切换前\n");
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "切换后\n");
+        std::fs::remove_file(&old).unwrap();
+        std::fs::remove_file(&new).unwrap();
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("clogger_handle_{}.log", std::process::id()));
+        std::fs::write(&path, "已有内容\n").unwrap();
+
+        // 未以追加模式打开的文件同样从末尾开始写入
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        let mut writer = LogFileWriter::new(Arc::new(
+            LogFile::from_file(file, FileOptions::default()).unwrap(),
+        ));
+        writeln!(writer, "新的日志").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.file.path(), None);
+        assert_eq!(
+            writer.file.reopen().unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "已有内容\n新的日志\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_utf8_bom() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("clogger_bom_{}.log", std::process::id()));
+        let rotated = dir.join(format!("clogger_bom_{}.log.1", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let options = FileOptions {
+            utf8_bom: true,
+            ..Default::default()
+        };
+        let mut writer = LogFileWriter::new(Arc::new(LogFile::open(&path, options).unwrap()));
+        writeln!(writer, "第一行").unwrap();
+        writer.flush().unwrap();
+        // 追加到已有内容的文件时不会再写入 BOM
+        let writer = LogFileWriter::new(Arc::new(LogFile::open(&path, options).unwrap()));
+        std::fs::rename(&path, &rotated).unwrap();
+        writer.file.reopen().unwrap();
+
+        assert_eq!(
+            std::fs::read(&rotated).unwrap(),
+            "\u{feff}第一行\n".as_bytes()
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), UTF8_BOM);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+
+    #[test]
+    fn test_buffered_flush_interval() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_buffered_{}.log", std::process::id()));
+        let file = Arc::new(
+            LogFile::open(
+                &path,
+                FileOptions {
+                    buffered: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+
+        let mut writer = LogFileWriter::new(file.clone());
+        writeln!(writer, "缓冲中的日志").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        assert_eq!(file.size().unwrap(), "缓冲中的日志\n".len() as u64);
+
+        file.sync().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "缓冲中的日志\n");
+
+        writeln!(writer, "另一条缓冲中的日志").unwrap();
+        spawn_flusher(&file, Duration::from_millis(10));
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "缓冲中的日志\n另一条缓冲中的日志\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_interactive_flush() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_interactive_{}.log", std::process::id()));
+        let options = FileOptions {
+            buffered: true,
+            interactive: true,
+            ..Default::default()
+        };
+        let mut writer = LogFileWriter::new(Arc::new(LogFile::open(&path, options).unwrap()));
+        writeln!(writer, "实时可见的日志").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "实时可见的日志\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_wrap_at_max_size() {
+        let path = std::env::temp_dir().join(format!("clogger_wrap_{}.log", std::process::id()));
+        let options = FileOptions {
+            wrap_size: Some(WRAP_HEADER_LEN + 20),
+            ..Default::default()
+        };
+        let mut writer = LogFileWriter::new(Arc::new(LogFile::open(&path, options).unwrap()));
+        for line in ["first-1\n", "second2\n", "third-3\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+            writer.flush().unwrap();
+        }
+
+        // 第三条日志超出上限，从头部标记之后覆盖最旧的日志
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            format!("{}third-3\nsecond2\n", wrap_header(WRAP_HEADER_LEN + 8))
+        );
+        assert!(contents.len() as u64 <= WRAP_HEADER_LEN + 20);
+
+        // 重新打开后从头部标记记录的位置继续写入
+        let mut writer = LogFileWriter::new(Arc::new(LogFile::open(&path, options).unwrap()));
+        writer.write_all(b"fourth4\n").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!("{}third-3\nfourth4\n", wrap_header(WRAP_HEADER_LEN + 16))
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
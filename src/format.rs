@@ -0,0 +1,1035 @@
+use crate::group::group_depth;
+use crate::theme::{decode_color, ColorTheme, Style};
+use crate::toggle::colors_override;
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use fern::Dispatch;
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// 日志的格式化配置。
+#[derive(Clone, Default)]
+pub(crate) struct Format {
+    /// 是否为输出内容着色。
+    pub(crate) color: bool,
+    /// 是否为终端输出，终端输出的着色状态可以通过 [`set_colors`](crate::set_colors) 在运行时修改。
+    pub(crate) console: bool,
+    /// 附加在每条日志中的标签（如应用版本）。
+    pub(crate) app_tag: Option<String>,
+    /// 用于获取时间戳的时钟，未设置时使用系统时间。
+    pub(crate) clock: Option<Clock>,
+    /// 时间戳的格式。
+    pub(crate) timestamp_mode: TimestampMode,
+    /// 着色时使用的配色主题。
+    pub(crate) theme: ColorTheme,
+    /// 是否转义日志信息中的控制字符。
+    pub(crate) sanitize_control: bool,
+    /// 模块名称及位置信息所占的列宽，超出时将被截断。
+    pub(crate) target_width: Option<usize>,
+    /// 字段之间的分隔符，设置后将不再使用括号包裹各字段。
+    pub(crate) separator: Option<String>,
+    /// 是否在模块名称后显示调用处所在 crate 的版本。
+    pub(crate) show_crate_version: bool,
+    /// 是否不显示调用位置。
+    pub(crate) hide_location: bool,
+    /// 是否不显示模块名称与调用位置。
+    pub(crate) hide_target: bool,
+    /// 单条日志内容的长度上限（字节），未设置时使用 [`DEFAULT_MAX_RECORD_LEN`]。
+    pub(crate) max_record_len: Option<usize>,
+    /// 附加在每条日志末尾的全局键值对。
+    pub(crate) global_fields: Arc<[(String, String)]>,
+    /// 需要隐藏值的键值对。
+    pub(crate) field_mask: FieldMask,
+    /// 用于移除日志内容与键值对中 ANSI 转义序列的函数，未设置时保留转义序列。
+    pub(crate) ansi_stripper: Option<AnsiStripper>,
+}
+
+/// 隐藏后的键值对的值。
+pub(crate) const MASKED_VALUE: &str = "***";
+
+/// 按名称隐藏键值对的值，用于 [`CloggerBuilder::mask_fields`](crate::CloggerBuilder::mask_fields)。
+#[derive(Clone, Default)]
+pub(crate) struct FieldMask {
+    names: Arc<[String]>,
+    case_sensitive: bool,
+}
+
+impl FieldMask {
+    /// 创建隐藏 `names` 中键值对的规则，`case_sensitive` 为匹配时是否区分大小写。
+    pub(crate) fn new(names: &[String], case_sensitive: bool) -> Self {
+        FieldMask {
+            names: names.into(),
+            case_sensitive,
+        }
+    }
+
+    /// 判断键为 `key` 的键值对是否需要隐藏。
+    pub(crate) fn masks(&self, key: &str) -> bool {
+        self.names.iter().any(|name| {
+            if self.case_sensitive {
+                name == key
+            } else {
+                name.eq_ignore_ascii_case(key)
+            }
+        })
+    }
+}
+
+/// 移除文本中 ANSI 转义序列的函数，用于 [`CloggerBuilder::file_ansi_stripper`](crate::CloggerBuilder::file_ansi_stripper)。
+pub(crate) type AnsiStripper = Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>;
+
+/// 单条日志内容默认的长度上限（1 MiB）。
+pub(crate) const DEFAULT_MAX_RECORD_LEN: usize = 1024 * 1024;
+
+/// 日志内容被截断时附加在末尾的标记。
+const TRUNCATED_MARKER: &str = " ...[truncated]";
+
+/// CLogger 宏附加在日志记录中、保存调用处 crate 版本的键。
+///
+/// 以 `__clogger_` 开头的键由 CLogger 内部使用，不会作为用户的键值对输出。
+pub(crate) const CRATE_VERSION_KEY: &str = "__clogger_crate_version";
+
+/// [`c_log_colored!`](crate::c_log_colored) 附加在日志记录中、保存日志信息颜色的键。
+pub(crate) const COLOR_KEY: &str = "__clogger_color";
+
+/// [`c_log_at_time!`](crate::c_log_at_time) 附加在日志记录中、保存日志时间戳（Unix 时间，微秒）的键。
+pub(crate) const TIMESTAMP_KEY: &str = "__clogger_timestamp";
+
+/// 全局 CLogger 是否显示模块名称与调用位置，未显示时 CLogger 宏不再构建包含位置信息的目标字符串。
+static TARGET_SHOWN: AtomicBool = AtomicBool::new(true);
+
+/// 设置全局 CLogger 是否显示模块名称与调用位置。
+pub(crate) fn set_target_shown(shown: bool) {
+    TARGET_SHOWN.store(shown, Ordering::Relaxed);
+}
+
+/// 全局 CLogger 是否将调用位置中的文件路径显示为相对于调用处 crate 根目录的路径。
+static RELATIVE_LOCATION: AtomicBool = AtomicBool::new(false);
+
+/// 设置全局 CLogger 是否将调用位置中的文件路径显示为相对路径。
+pub(crate) fn set_relative_location(relative: bool) {
+    RELATIVE_LOCATION.store(relative, Ordering::Relaxed);
+}
+
+/// 构建 CLogger 宏使用的日志目标 `模块名称 (文件:行号^列号)`。
+///
+/// 全局 CLogger 不显示日志目标时只返回模块名称，以免为每条日志构建不会被显示的字符串。
+/// `manifest_dir` 为调用处 crate 的 `CARGO_MANIFEST_DIR`，用于显示相对路径。
+pub fn macro_target<'a, M: AsRef<str> + ?Sized>(
+    module: &'a M,
+    location: &Location,
+    manifest_dir: Option<&str>,
+) -> Cow<'a, str> {
+    let module = module.as_ref();
+    if !TARGET_SHOWN.load(Ordering::Relaxed) {
+        return Cow::Borrowed(module);
+    }
+    let mut target = String::with_capacity(module.len() + location_len(location));
+    target.push_str(module);
+    push_location(&mut target, location, manifest_dir);
+    Cow::Owned(target)
+}
+
+/// 与 [`macro_target`] 相同，但直接在传入的模块名称后追加位置信息，不再分配新的字符串。
+pub fn macro_target_owned(
+    mut module: String,
+    location: &Location,
+    manifest_dir: Option<&str>,
+) -> String {
+    if TARGET_SHOWN.load(Ordering::Relaxed) {
+        module.reserve(location_len(location));
+        push_location(&mut module, location, manifest_dir);
+    }
+    module
+}
+
+/// 位置信息 ` (文件:行号^列号)` 的最大长度，用于预先分配容量。
+fn location_len(location: &Location) -> usize {
+    // 空格、括号、冒号与 `^` 共 5 个字符，行号与列号各不超过 10 位
+    location.file().len() + 25
+}
+
+/// 在日志目标后追加位置信息 ` (文件:行号^列号)`。
+fn push_location(target: &mut String, location: &Location, manifest_dir: Option<&str>) {
+    let file = match manifest_dir.filter(|_| RELATIVE_LOCATION.load(Ordering::Relaxed)) {
+        Some(manifest_dir) => relative_path(location.file(), manifest_dir),
+        None => location.file(),
+    };
+    let _ = write!(
+        target,
+        " ({}:{}^{})",
+        file,
+        location.line(),
+        location.column()
+    );
+}
+
+/// 获取 `file` 相对于 `root` 的路径，`file` 不在 `root` 中时原样返回。
+fn relative_path<'a>(file: &'a str, root: &str) -> &'a str {
+    let root = root.trim_end_matches(['/', '\\']);
+    file.strip_prefix(root)
+        .and_then(|rest| rest.strip_prefix(['/', '\\']))
+        .unwrap_or(file)
+}
+
+/// 获取日志记录中指定的时间戳。
+pub(crate) fn record_time(record: &log::Record) -> Option<DateTime<Local>> {
+    let micros = record
+        .key_values()
+        .get(log::kv::Key::from_str(TIMESTAMP_KEY))?
+        .to_i64()?;
+    Some(DateTime::from_timestamp_micros(micros)?.with_timezone(&Local))
+}
+
+/// 判断键值对的键是否由 CLogger 内部使用。
+pub(crate) fn is_reserved_key(key: &str) -> bool {
+    key.starts_with("__clogger_")
+}
+
+/// 时间戳的格式，用于 [`CloggerBuilder::timestamp_mode`](crate::CloggerBuilder::timestamp_mode)。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{CloggerBuilder, TimestampMode};
+///
+/// // 输出形如 `2024-06-01T13:45:12.123+08:00` 的时间戳，便于日志收集系统解析
+/// CloggerBuilder::new()
+///     .log_file("/tmp/clogger_example.log")
+///     .timestamp_mode(TimestampMode::Rfc3339)
+///     .init();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimestampMode {
+    /// 本地时间，形如 `2024-06-01 13:45:12.123`（默认）。
+    #[default]
+    Local,
+    /// UTC 时间，形如 `2024-06-01 05:45:12.123Z`。
+    Utc,
+    /// 包含时区偏移的 RFC 3339（ISO 8601）格式，形如 `2024-06-01T13:45:12.123+08:00`。
+    Rfc3339,
+    /// 使用自定义的 [`chrono` 格式字符串](chrono::format::strftime)格式化本地时间。
+    Custom(String),
+}
+
+impl TimestampMode {
+    /// 按照该格式将时间转换为文本。
+    fn format<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: fmt::Display,
+    {
+        match self {
+            TimestampMode::Local => time.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            TimestampMode::Utc => time
+                .with_timezone(&Utc)
+                .format("%Y-%m-%d %H:%M:%S%.3fZ")
+                .to_string(),
+            TimestampMode::Rfc3339 => time.to_rfc3339_opts(SecondsFormat::Millis, false),
+            TimestampMode::Custom(format) => time.format(format).to_string(),
+        }
+    }
+}
+
+/// 提供当前时间的时钟。
+pub(crate) type Clock = Arc<dyn Fn() -> DateTime<Local> + Send + Sync>;
+
+impl Format {
+    /// 判断是否为输出内容着色，`colors` 为通过 [`set_colors`](crate::set_colors) 设置的状态，终端输出优先使用该设置。
+    fn color_enabled(&self, colors: Option<bool>) -> bool {
+        match colors {
+            Some(enabled) if self.console => enabled,
+            _ => self.color,
+        }
+    }
+
+    /// 将一条日志记录格式化为最终输出的文本（不包含换行符）。
+    pub(crate) fn render(&self, message: &fmt::Arguments, record: &log::Record) -> String {
+        let style = self.theme.level(record.level());
+        let enabled = self.color_enabled(colors_override());
+        // 主题可以单独取消某个级别的着色
+        let color = enabled && style.colored;
+        let timestamp = self
+            .timestamp_mode
+            .format(&record_time(record).unwrap_or_else(|| self.now()));
+        let timestamp = paint(color, &timestamp, &self.theme.timestamp);
+        let level = paint(color, &style.marker, &style.marker_style);
+        // 按显示宽度对齐级别标记，使用宽度不同的符号作为标记时各行仍能对齐
+        let level_padding = self.theme.marker_width() - style.marker.width();
+        let (target, target_padding) = if self.hide_target {
+            (Cow::Borrowed(""), 0)
+        } else {
+            let target = self.display_target(record);
+            let target = match self.target_width {
+                Some(width) => Cow::Owned(truncate_to_width(&target, width).into_owned()),
+                None => target,
+            };
+            let target_padding = self
+                .target_width
+                .map_or(0, |width| width.saturating_sub(target.width()));
+            (target, target_padding)
+        };
+        let message = capped_message(
+            message,
+            self.max_record_len.unwrap_or(DEFAULT_MAX_RECORD_LEN),
+        );
+        let mut message = match &self.ansi_stripper {
+            Some(strip) => strip(&message),
+            None => Cow::Borrowed(message.as_str()),
+        };
+        if self.sanitize_control {
+            if let Cow::Owned(escaped) = escape_control(&message) {
+                message = Cow::Owned(escaped);
+            }
+        }
+        // 按照当前线程中嵌套的分组缩进日志内容
+        let depth = group_depth();
+        let message = if depth > 0 {
+            Cow::Owned(format!("{}{}", "  ".repeat(depth), message))
+        } else {
+            message
+        };
+        // 单条日志指定的颜色优先于级别的样式
+        let message = match record
+            .key_values()
+            .get(log::kv::Key::from_str(COLOR_KEY))
+            .and_then(|code| decode_color(code.to_u64()?))
+        {
+            Some(message_color) => paint(enabled, &message, &Style::new().color(message_color)),
+            None => paint(color, &message, &style.message),
+        };
+        let target = paint(color, &target, &self.theme.target);
+        if let Some(separator) = &self.separator {
+            // 使用分隔符时不添加括号与对齐用的空格，便于按分隔符拆分
+            let mut line = format!("{}{}", timestamp, separator);
+            if let Some(app_tag) = &self.app_tag {
+                let _ = write!(line, "{}{}", app_tag, separator);
+            }
+            let _ = write!(line, "{}{}", level, separator);
+            if !self.hide_target {
+                let _ = write!(line, "{}{}", target, separator);
+            }
+            let _ = write!(line, "{}", message);
+            self.write_fields(&mut line, record);
+            return line;
+        }
+        let mut line = format!("({}) ", timestamp);
+        if let Some(app_tag) = &self.app_tag {
+            let _ = write!(line, "[{}] ", app_tag);
+        }
+        let _ = write!(line, "[{}]{:level_padding$} ", level, "");
+        if !self.hide_target {
+            let _ = write!(line, "[{}]{:target_padding$} ", target, "");
+        }
+        let _ = write!(line, "{}", message);
+        self.write_fields(&mut line, record);
+        line
+    }
+
+    /// 将日志记录中的键值对以 ` 键=值` 的形式追加到日志末尾，CLogger 内部使用的键会被跳过，最后追加全局的键值对。
+    ///
+    /// 包含空白字符、引号或 `=` 的值会被加上引号并转义，便于按空格拆分。
+    fn write_fields(&self, line: &mut String, record: &log::Record) {
+        struct Visitor<'a> {
+            line: &'a mut String,
+            format: &'a Format,
+        }
+
+        impl<'kvs> log::kv::VisitSource<'kvs> for Visitor<'_> {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                if !is_reserved_key(key.as_str()) {
+                    self.format
+                        .write_field(self.line, key.as_str(), &value.to_string());
+                }
+                Ok(())
+            }
+        }
+
+        let _ = record
+            .key_values()
+            .visit(&mut Visitor { line, format: self });
+        for (key, value) in self.global_fields.iter() {
+            self.write_field(line, key, value);
+        }
+    }
+
+    /// 将一个键值对以 ` 键=值` 的形式追加到日志末尾。
+    fn write_field(&self, line: &mut String, key: &str, value: &str) {
+        if self.field_mask.masks(key) {
+            let _ = write!(line, " {}={}", key, MASKED_VALUE);
+            return;
+        }
+        let value = match &self.ansi_stripper {
+            Some(strip) => strip(value),
+            None => Cow::Borrowed(value),
+        };
+        if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+            let _ = write!(line, " {}={:?}", key, value);
+        } else if self.sanitize_control {
+            let _ = write!(line, " {}={}", key, escape_control(&value));
+        } else {
+            let _ = write!(line, " {}={}", key, value);
+        }
+    }
+
+    /// 获取用于显示的日志目标。
+    ///
+    /// 直接使用 `log` 宏（如第三方库中的 `log::info!`）产生的日志目标中没有位置信息，
+    /// 此时会使用 `log` 记录的文件与行号补全，使其与 CLogger 宏产生的日志格式一致。
+    /// 启用 `show_crate_version` 时，会在模块名称后附加 `@版本`；启用 `hide_location` 时只保留模块名称。
+    fn display_target<'a>(&self, record: &log::Record<'a>) -> Cow<'a, str> {
+        let target = record.target();
+        let (module, location) = split_target(target);
+        let version = self
+            .show_crate_version
+            .then(|| {
+                record
+                    .key_values()
+                    .get(log::kv::Key::from_str(CRATE_VERSION_KEY))
+            })
+            .flatten();
+        if self.hide_location {
+            return match version {
+                Some(version) => Cow::Owned(format!("{}@{}", module, version)),
+                None => Cow::Borrowed(module),
+            };
+        }
+        match (location, record.file(), record.line(), version) {
+            (None, Some(file), Some(line), version) => {
+                let version = version.map(|v| format!("@{}", v)).unwrap_or_default();
+                Cow::Owned(format!("{}{} ({}:{})", module, version, file, line))
+            }
+            (Some(location), _, _, Some(version)) => {
+                Cow::Owned(format!("{}@{} ({})", module, version, location))
+            }
+            (None, _, _, Some(version)) => Cow::Owned(format!("{}@{}", module, version)),
+            _ => Cow::Borrowed(target),
+        }
+    }
+
+    /// 获取当前时间。
+    fn now(&self) -> DateTime<Local> {
+        match &self.clock {
+            Some(clock) => clock(),
+            None => Local::now(),
+        }
+    }
+
+    /// 创建一个使用该格式输出日志的 [`Dispatch`]。
+    pub(crate) fn into_dispatch(self) -> Dispatch {
+        Dispatch::new().format(move |out, message, record| {
+            out.finish(format_args!("{}", self.render(message, record)))
+        })
+    }
+}
+
+/// 在启用着色时为文本应用样式，否则原样返回。
+fn paint<'a>(color: bool, text: &'a str, style: &Style) -> Cow<'a, str> {
+    if color {
+        style.paint(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// 将日志内容格式化为字符串，超过 `limit` 字节的部分会被丢弃并以 [`TRUNCATED_MARKER`] 结尾。
+///
+/// 格式化在达到上限时立即停止，不会先构建完整的字符串，因此意外记录的超大内容不会耗尽内存。
+/// 第一次发生截断时会在标准错误中输出一条警告。
+pub(crate) fn capped_message(message: &fmt::Arguments, limit: usize) -> String {
+    /// 达到上限后拒绝继续写入的缓冲区。
+    struct Capped {
+        text: String,
+        limit: usize,
+        truncated: bool,
+    }
+
+    impl Write for Capped {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let remaining = self.limit - self.text.len();
+            if s.len() <= remaining {
+                self.text.push_str(s);
+                return Ok(());
+            }
+            // 在字符边界处截断
+            let mut end = remaining;
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.text.push_str(&s[..end]);
+            // 截断处位于字素簇（如带肤色的表情、国旗、组合字符）中间时，移除不完整的字素簇
+            if let Some(next) = s[end..].chars().next() {
+                if let Some((start, last)) = self.text.grapheme_indices(true).next_back() {
+                    let mut joined = last.to_string();
+                    joined.push(next);
+                    if joined.graphemes(true).count() == 1 {
+                        self.text.truncate(start);
+                    }
+                }
+            }
+            self.truncated = true;
+            Err(fmt::Error)
+        }
+    }
+
+    static WARNED: AtomicBool = AtomicBool::new(false);
+    let mut capped = Capped {
+        text: String::new(),
+        limit,
+        truncated: false,
+    };
+    let _ = capped.write_fmt(*message);
+    if capped.truncated {
+        capped.text.push_str(TRUNCATED_MARKER);
+        if !WARNED.swap(true, Ordering::Relaxed) {
+            eprintln!(
+                "CLogger 警告: 一条日志的内容超过了 {} 字节的长度上限，超出的部分已被截断",
+                limit
+            );
+        }
+    }
+    capped.text
+}
+
+/// 将操作的说明、错误及其 `source()` 链拼接为 `说明: 错误: 原因: ...` 形式的文本，供 [`c_try!`](crate::c_try) 使用。
+#[doc(hidden)]
+pub fn error_chain(context: &dyn fmt::Display, error: &dyn std::error::Error) -> String {
+    let mut text = format!("{}: {}", context, error);
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let _ = write!(text, ": {}", cause);
+        source = cause.source();
+    }
+    text
+}
+
+/// 将 `anyhow::Error` 及其上下文链格式化为多行文本，供 [`c_anyhow!`](crate::c_anyhow) 使用。
+///
+/// 第一行为最外层的错误，之后每个上下文（由外到内）各占一行并缩进；错误捕获了堆栈回溯时
+/// （通常需要设置 `RUST_BACKTRACE` 或 `RUST_LIB_BACKTRACE` 环境变量），会在最后附加堆栈回溯。
+#[cfg(feature = "anyhow")]
+#[doc(hidden)]
+pub fn anyhow_report(error: &anyhow::Error) -> String {
+    let mut text = error.to_string();
+    for cause in error.chain().skip(1) {
+        let _ = write!(text, "\n    原因: {}", cause);
+    }
+    let backtrace = error.backtrace();
+    if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+        let _ = write!(text, "\n堆栈回溯:\n{}", backtrace);
+    }
+    text
+}
+
+/// 以标准的 base64 编码（带填充）编码二进制数据，供 [`c_base64!`](crate::c_base64) 使用。
+#[cfg(feature = "base64")]
+#[doc(hidden)]
+pub fn base64_encode(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// 将 CLogger 宏生成的日志目标拆分为模块名称与位置信息。
+///
+/// 宏生成的目标形如 `模块名称 (文件:行号^列号)`，返回 `(模块名称, Some("文件:行号^列号"))`；
+/// 其他来源（如直接使用 `log` 宏）的目标会原样作为模块名称返回。
+pub(crate) fn split_target(target: &str) -> (&str, Option<&str>) {
+    match target
+        .strip_suffix(')')
+        .and_then(|target| target.split_once(" ("))
+    {
+        Some((module, location)) => (module, Some(location)),
+        None => (target, None),
+    }
+}
+
+/// 解析 `文件:行号^列号` 或 `文件:行号` 形式的位置信息。
+///
+/// 从右侧拆分，因此 Windows 路径中盘符后的冒号（如 `C:\src\main.rs:42^7`）不会影响解析。
+/// 返回 `(文件, 行号, 列号)`，供需要结构化位置信息的场景（如 [`parse_line`](crate::parse_line)）使用。
+pub(crate) fn parse_location(location: &str) -> Option<(&str, u32, Option<u32>)> {
+    let (file, position) = location.rsplit_once(':')?;
+    match position.split_once('^') {
+        Some((line, column)) => Some((file, line.parse().ok()?, Some(column.parse().ok()?))),
+        None => Some((file, position.parse().ok()?, None)),
+    }
+}
+
+/// 按显示宽度截断文本，被截断的文本以 `…` 结尾，且总宽度不超过 `width`。
+///
+/// 中日韩文字等宽字符按两列计算。按字素簇截断，表情符号与组合字符不会被拆开。
+fn truncate_to_width(text: &str, width: usize) -> Cow<'_, str> {
+    if text.width() <= width {
+        return Cow::Borrowed(text);
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if used + grapheme_width + 1 > width {
+            break;
+        }
+        used += grapheme_width;
+        truncated.push_str(grapheme);
+    }
+    if width > 0 {
+        truncated.push('…');
+    }
+    Cow::Owned(truncated)
+}
+
+/// 将文本中的控制字符（换行符与制表符除外）转义为 `\xNN` 的形式。
+fn escape_control(text: &str) -> Cow<'_, str> {
+    let needs_escape = |c: char| c.is_control() && c != '\n' && c != '\t';
+    if !text.chars().any(needs_escape) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if needs_escape(c) {
+            let _ = write!(escaped, "\\x{:02x}", c as u32);
+        } else {
+            escaped.push(c);
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use colored::Color;
+
+    #[test]
+    fn test_plain_render() {
+        let format = Format {
+            color: false,
+            app_tag: Some("v1.4.2+abc123".to_string()),
+            ..Default::default()
+        };
+        let line = format.render(
+            &format_args!("这是一条警告日志！"),
+            &log::Record::builder()
+                .level(log::Level::Warn)
+                .target("clogger::tests")
+                .build(),
+        );
+        assert!(!line.contains('\x1b'));
+        assert!(line.ends_with(" [v1.4.2+abc123] [W] [clogger::tests] 这是一条警告日志！"));
+    }
+
+    #[test]
+    fn test_fixed_clock() {
+        let format = Format {
+            clock: Some(Arc::new(|| {
+                Local.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()
+            })),
+            ..Default::default()
+        };
+        let line = format.render(
+            &format_args!("这是一条常规日志！"),
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .target("clogger::tests")
+                .build(),
+        );
+        assert_eq!(
+            line,
+            "(2024-06-01 12:00:00.000) [I] [clogger::tests] 这是一条常规日志！"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_mode() {
+        let time = FixedOffset::east_opt(8 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 6, 1, 13, 45, 12)
+            .unwrap()
+            + chrono::Duration::milliseconds(123);
+        assert_eq!(
+            TimestampMode::Rfc3339.format(&time),
+            "2024-06-01T13:45:12.123+08:00"
+        );
+        assert_eq!(TimestampMode::Utc.format(&time), "2024-06-01 05:45:12.123Z");
+        assert_eq!(
+            TimestampMode::Custom("%H:%M:%S".to_string()).format(&time),
+            "13:45:12"
+        );
+    }
+
+    #[test]
+    fn test_raw_log_location() {
+        let format = Format::default();
+        let render = |target: &str| {
+            format.render(
+                &format_args!("内容"),
+                &log::Record::builder()
+                    .level(log::Level::Info)
+                    .target(target)
+                    .file(Some("src/net.rs"))
+                    .line(Some(42))
+                    .build(),
+            )
+        };
+        // 直接使用 `log` 宏的日志使用记录中的文件与行号补全位置信息
+        assert!(render("mylib::net").ends_with("[mylib::net (src/net.rs:42)] 内容"));
+        // CLogger 宏的日志保留宏自身生成的位置信息
+        assert!(render("app (src/main.rs:7^5)").ends_with("[app (src/main.rs:7^5)] 内容"));
+    }
+
+    #[test]
+    fn test_hide_target() {
+        let render = |separator: Option<&str>| {
+            Format {
+                hide_target: true,
+                target_width: Some(20),
+                separator: separator.map(str::to_string),
+                ..Default::default()
+            }
+            .render(
+                &format_args!("内容"),
+                &log::Record::builder()
+                    .level(log::Level::Info)
+                    .target("app (src/main.rs:7^5)")
+                    .build(),
+            )
+        };
+        assert!(render(None).ends_with(") [I] 内容"));
+        assert!(render(Some("|")).ends_with("|I|内容"));
+    }
+
+    #[test]
+    fn test_show_crate_version() {
+        let format = Format {
+            show_crate_version: true,
+            ..Default::default()
+        };
+        let line = format.render(
+            &format_args!("内容"),
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .target("app (src/main.rs:7^5)")
+                .key_values(&[(CRATE_VERSION_KEY, "1.4.2")])
+                .build(),
+        );
+        assert!(line.ends_with("[app@1.4.2 (src/main.rs:7^5)] 内容"));
+    }
+
+    #[test]
+    fn test_uncolored_level() {
+        let format = Format {
+            color: true,
+            theme: ColorTheme::default().uncolored(log::Level::Info),
+            ..Default::default()
+        };
+        let render = |level| {
+            format.render(
+                &format_args!("内容"),
+                &log::Record::builder()
+                    .level(level)
+                    .target("clogger::tests")
+                    .build(),
+            )
+        };
+        assert!(!render(log::Level::Info).contains('\x1b'));
+        assert!(render(log::Level::Error).contains('\x1b'));
+    }
+
+    #[test]
+    fn test_runtime_colors() {
+        let console = Format {
+            console: true,
+            ..Default::default()
+        };
+        assert!(!console.color_enabled(None));
+        assert!(console.color_enabled(Some(true)));
+        let console = Format {
+            color: true,
+            ..console
+        };
+        assert!(!console.color_enabled(Some(false)));
+        // 日志文件等其他输出端不受运行时设置的影响
+        let file = Format::default();
+        assert!(!file.color_enabled(Some(true)));
+    }
+
+    #[test]
+    fn test_message_color_override() {
+        let format = Format {
+            color: true,
+            ..Default::default()
+        };
+        let code = crate::theme::encode_color(Color::BrightGreen);
+        let line = format.render(
+            &format_args!("部署成功"),
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .target("clogger::tests")
+                .key_values(&[(COLOR_KEY, code)])
+                .build(),
+        );
+        assert!(line.ends_with("\x1b[92m部署成功\x1b[0m"));
+    }
+
+    #[test]
+    fn test_separator() {
+        let format = Format {
+            clock: Some(Arc::new(|| {
+                Local.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()
+            })),
+            timestamp_mode: TimestampMode::Custom("%Y-%m-%d %H:%M:%S".to_string()),
+            separator: Some("|".to_string()),
+            target_width: Some(20),
+            ..Default::default()
+        };
+        let line = format.render(
+            &format_args!("内容"),
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .target("clogger::tests")
+                .build(),
+        );
+        assert_eq!(line, "2024-06-01 12:00:00|I|clogger::tests|内容");
+    }
+
+    #[test]
+    fn test_record_time_override() {
+        let format = Format {
+            timestamp_mode: TimestampMode::Utc,
+            ..Default::default()
+        };
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let line = format.render(
+            &format_args!("订单已创建"),
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .target("clogger::tests")
+                .key_values(&[(TIMESTAMP_KEY, time.timestamp_micros())])
+                .build(),
+        );
+        assert!(line.starts_with("(2024-06-01 12:00:00.000Z) "));
+    }
+
+    #[test]
+    fn test_escape_control() {
+        assert_eq!(escape_control("正常的日志"), "正常的日志");
+        assert_eq!(escape_control("多行\n日志\t内容"), "多行\n日志\t内容");
+        assert_eq!(
+            escape_control("伪造\r(2024-06-01) [E] \x1b[31m"),
+            "伪造\\x0d(2024-06-01) [E] \\x1b[31m"
+        );
+        assert_eq!(escape_control("\u{85}"), "\\x85");
+    }
+
+    #[test]
+    fn test_max_record_len() {
+        let format = Format {
+            max_record_len: Some(10),
+            ..Default::default()
+        };
+        let huge = "长".repeat(1000);
+        let line = format.render(
+            &format_args!("{}", huge),
+            &log::Record::builder()
+                .level(log::Level::Debug)
+                .target("app")
+                .build(),
+        );
+        // 每个汉字占 3 字节，截断在字符边界处
+        assert!(line.ends_with(&format!("[app] 长长长{}", TRUNCATED_MARKER)));
+    }
+
+    #[test]
+    fn test_grapheme_truncation() {
+        let capped = |text: &str, limit| capped_message(&format_args!("{}", text), limit);
+        // 带肤色的表情、国旗与组合字符不会被拆开
+        assert_eq!(capped("👍🏽👍🏽", 6), TRUNCATED_MARKER);
+        assert_eq!(capped("👍🏽👍🏽", 9), format!("👍🏽{}", TRUNCATED_MARKER));
+        assert_eq!(capped("🇨🇳🇯🇵", 12), format!("🇨🇳{}", TRUNCATED_MARKER));
+        assert_eq!(capped("ae\u{301}", 2), format!("a{}", TRUNCATED_MARKER));
+        assert_eq!(truncate_to_width("🇨🇳🇯🇵", 3), "🇨🇳…");
+        assert_eq!(truncate_to_width("app::👨‍👩‍👧🚀", 8), "app::👨‍👩‍👧…");
+    }
+
+    #[test]
+    fn test_error_chain() {
+        #[derive(Debug)]
+        struct ConfigError(std::io::Error);
+
+        impl fmt::Display for ConfigError {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("无法读取配置文件")
+            }
+        }
+
+        impl std::error::Error for ConfigError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let error = ConfigError(std::io::Error::other("权限不足"));
+        assert_eq!(
+            error_chain(&"加载配置", &error),
+            "加载配置: 无法读取配置文件: 权限不足"
+        );
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn test_anyhow_report() {
+        use anyhow::Context;
+
+        let error = Err::<(), _>(std::io::Error::other("权限不足"))
+            .context("读取 config.toml")
+            .context("无法加载配置")
+            .unwrap_err();
+        let report = anyhow_report(&error);
+        assert!(report.starts_with("无法加载配置\n    原因: 读取 config.toml\n    原因: 权限不足"));
+    }
+
+    #[test]
+    fn test_render_fields() {
+        let line = Format::default().render(
+            &format_args!("请求完成"),
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .target("app")
+                .key_values(&[
+                    ("status", "200"),
+                    ("path", "/api/用户 列表"),
+                    (CRATE_VERSION_KEY, "1.4.2"),
+                ])
+                .build(),
+        );
+        assert!(line.ends_with(r#"[app] 请求完成 status=200 path="/api/用户 列表""#));
+    }
+
+    #[test]
+    fn test_global_fields() {
+        let format = Format {
+            global_fields: Arc::from([
+                ("service".to_string(), "checkout".to_string()),
+                ("region".to_string(), "us east".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let line = format.render(
+            &format_args!("请求完成"),
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .target("app")
+                .key_values(&[("status", "200")])
+                .build(),
+        );
+        assert!(line.ends_with(r#"[app] 请求完成 status=200 service=checkout region="us east""#));
+    }
+
+    #[test]
+    fn test_macro_target_owned() {
+        let location = Location::caller();
+        let mut module = String::with_capacity(64);
+        module.push_str("app");
+        let buffer = module.as_ptr();
+
+        let target = macro_target_owned(module, location, None);
+        assert_eq!(target, macro_target("app", location, None));
+        // 容量足够时复用传入字符串的内存
+        assert_eq!(target.as_ptr(), buffer);
+    }
+
+    #[test]
+    fn test_mask_fields() {
+        let format = Format {
+            field_mask: FieldMask::new(&["password".to_string()], false),
+            ..Default::default()
+        };
+        let record = log::Record::builder()
+            .level(log::Level::Info)
+            .target("app")
+            .key_values(&[("user", "chengcheng"), ("Password", "hunter2")])
+            .build();
+        let line = format.render(&format_args!("登录"), &record);
+        assert!(line.ends_with("登录 user=chengcheng Password=***"));
+
+        // 区分大小写时只隐藏名称完全相同的键值对
+        let format = Format {
+            field_mask: FieldMask::new(&["password".to_string()], true),
+            ..Default::default()
+        };
+        let line = format.render(&format_args!("登录"), &record);
+        assert!(line.ends_with("Password=hunter2"));
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path("/home/dev/app/src/main.rs", "/home/dev/app"),
+            "src/main.rs"
+        );
+        assert_eq!(
+            relative_path(r"C:\Users\dev\app\src\main.rs", r"C:\Users\dev\app\"),
+            r"src\main.rs"
+        );
+        // 不在根目录中的文件与前缀相同的兄弟目录保持原样
+        assert_eq!(
+            relative_path("/home/dev/app2/src/lib.rs", "/home/dev/app"),
+            "/home/dev/app2/src/lib.rs"
+        );
+        assert_eq!(relative_path("src/main.rs", "/home/dev/app"), "src/main.rs");
+    }
+
+    #[test]
+    fn test_parse_location() {
+        let (_, location) = split_target(r"app (C:\Program Files (x86)\app\main.rs:42^7)");
+        assert_eq!(
+            location.and_then(parse_location),
+            Some((r"C:\Program Files (x86)\app\main.rs", 42, Some(7)))
+        );
+        assert_eq!(
+            parse_location(r"D:\src\lib.rs:9"),
+            Some((r"D:\src\lib.rs", 9, None))
+        );
+        // 缺少行号时不会把盘符误认为文件名
+        assert_eq!(parse_location(r"C:\src\lib.rs"), None);
+    }
+
+    #[test]
+    fn test_target_width_alignment() {
+        let format = Format {
+            target_width: Some(12),
+            ..Default::default()
+        };
+        let render = |target: &str| {
+            format.render(
+                &format_args!("内容"),
+                &log::Record::builder()
+                    .level(log::Level::Info)
+                    .target(target)
+                    .build(),
+            )
+        };
+        // 模块名称中的中文按两列计算，各行的日志内容对齐
+        let short = render("app::网络");
+        let exact = render("app::数据库a");
+        let long = render("app::数据库连接池");
+        assert!(short.ends_with("[app::网络]    内容"));
+        assert!(exact.ends_with("[app::数据库a] 内容"));
+        assert!(long.ends_with("[app::数据库…] 内容"));
+        let column = |line: &str| line.split_once("内容").unwrap().0.width();
+        assert_eq!(column(&short), column(&exact));
+        assert_eq!(column(&short), column(&long));
+    }
+}
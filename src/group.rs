@@ -0,0 +1,68 @@
+use std::cell::Cell;
+
+thread_local! {
+    /// 当前线程中嵌套的分组层数。
+    static GROUP_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// 获取当前线程中嵌套的分组层数。
+pub(crate) fn group_depth() -> usize {
+    GROUP_DEPTH.with(Cell::get)
+}
+
+/// 缩进日志的 RAII 守卫，通常通过 [`c_group!`](crate::c_group) 创建。
+///
+/// 守卫存在期间，当前线程输出的所有日志的内容都会增加一层缩进，离开作用域时恢复。
+/// 与 [`ScopeGuard`](crate::ScopeGuard) 不同，该守卫不会计时，也不会输出进入与离开的日志。
+pub struct GroupGuard {
+    depth: usize,
+}
+
+impl GroupGuard {
+    /// 进入分组，增加一层缩进。
+    #[doc(hidden)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let depth = GROUP_DEPTH.with(|d| {
+            let depth = d.get();
+            d.set(depth + 1);
+            depth
+        });
+        GroupGuard { depth }
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        GROUP_DEPTH.with(|d| d.set(self.depth));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    #[test]
+    fn test_group_indent() {
+        let format = Format::default();
+        let render = || {
+            format.render(
+                &format_args!("内容"),
+                &log::Record::builder()
+                    .level(log::Level::Info)
+                    .target("clogger::tests")
+                    .build(),
+            )
+        };
+        assert!(render().ends_with("] 内容"));
+        {
+            let _outer = GroupGuard::new();
+            let _inner = GroupGuard::new();
+            assert_eq!(group_depth(), 2);
+            assert!(render().ends_with("]     内容"));
+        }
+        assert_eq!(group_depth(), 0);
+        assert!(render().ends_with("] 内容"));
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 记录某个调用位置上一次输出心跳日志的时间，供 [`c_heartbeat!`](crate::c_heartbeat) 使用。
+///
+/// 每个宏调用位置拥有一个独立的静态实例，因此同一位置在多个线程中共享同一个间隔。
+pub struct Heartbeat {
+    last: Mutex<Option<Instant>>,
+}
+
+impl Heartbeat {
+    /// 创建一个尚未开始计时的实例。
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Heartbeat {
+            last: Mutex::new(None),
+        }
+    }
+
+    /// 判断距离上一次心跳是否已经过了 `interval`，是则记录当前时间并返回 `true`。
+    ///
+    /// 第一次调用时只开始计时，不会返回 `true`。
+    pub fn ready(&self, interval: Duration) -> bool {
+        let now = Instant::now();
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        match *last {
+            Some(time) if now.duration_since(time) < interval => false,
+            Some(_) => {
+                *last = Some(now);
+                true
+            }
+            None => {
+                *last = Some(now);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ready() {
+        let heartbeat = Heartbeat::new();
+        assert!(!heartbeat.ready(Duration::ZERO));
+        assert!(heartbeat.ready(Duration::ZERO));
+        assert!(!heartbeat.ready(Duration::from_secs(60)));
+
+        let heartbeat = Heartbeat::new();
+        heartbeat.ready(Duration::from_millis(20));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(heartbeat.ready(Duration::from_millis(20)));
+        assert!(!heartbeat.ready(Duration::from_millis(20)));
+    }
+}
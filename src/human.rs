@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+/// 将时长格式化为便于阅读的文本。
+///
+/// 不足 1 秒时使用 `ns`、`µs` 或 `ms` 中合适的单位（保留一位小数），不足 1 分钟时以秒为单位并保留两位小数，
+/// 更长的时长则四舍五入到整秒并拆分为 `2m03s` 或 `1h02m03s` 的形式。四舍五入后达到下一个单位时会进位，
+/// 如 `999.96ms` 输出为 `1.00s`。[`c_scope!`](crate::c_scope) 输出的耗时使用该格式。
+///
+/// # 示例
+/// ```rust
+/// use clogger::human_duration;
+/// use std::time::Duration;
+///
+/// assert_eq!(human_duration(Duration::from_micros(1500)), "1.5ms");
+/// assert_eq!(human_duration(Duration::from_millis(1234)), "1.23s");
+/// assert_eq!(human_duration(Duration::from_secs(123)), "2m03s");
+/// ```
+///
+/// # 参数
+/// - `duration`: 需要格式化的时长。
+pub fn human_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        return format!("{}ns", nanos);
+    }
+    // 四舍五入后达到下一个单位时进位，避免输出 `1000.0µs` 或 `60.00s`
+    if nanos < 999_950 {
+        return format!("{:.1}µs", nanos as f64 / 1e3);
+    }
+    if nanos < 999_950_000 {
+        return format!("{:.1}ms", nanos as f64 / 1e6);
+    }
+    if nanos < 59_995_000_000 {
+        return format!("{:.2}s", duration.as_secs_f64());
+    }
+    let secs = duration.as_secs() + u64::from(duration.subsec_nanos() >= 500_000_000);
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours == 0 {
+        format!("{}m{:02}s", minutes, secs)
+    } else {
+        format!("{}h{:02}m{:02}s", hours, minutes, secs)
+    }
+}
+
+/// 将字节数格式化为便于阅读的文本。
+///
+/// 不足 1 KiB 时直接输出字节数（如 `512 B`），否则使用 `KiB`、`MiB`、`GiB`、`TiB`、`PiB` 或 `EiB`
+/// 中合适的二进制单位（1 KiB = 1024 字节）并保留两位小数。[`c_sizeof!`](crate::c_sizeof) 与 `c_alloc!`
+/// 输出的大小使用该格式。
+///
+/// # 示例
+/// ```rust
+/// use clogger::human_bytes;
+///
+/// assert_eq!(human_bytes(512), "512 B");
+/// assert_eq!(human_bytes(1_290_000), "1.23 MiB");
+/// ```
+///
+/// # 参数
+/// - `bytes`: 需要格式化的字节数。
+pub fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // 四舍五入后达到 1024 时进位到下一个单位，避免输出 `1024.00 KiB`
+    while value >= 1024.0 - 0.005 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_duration() {
+        assert_eq!(human_duration(Duration::ZERO), "0ns");
+        assert_eq!(human_duration(Duration::from_nanos(850)), "850ns");
+        assert_eq!(human_duration(Duration::from_nanos(12_345)), "12.3µs");
+        assert_eq!(human_duration(Duration::from_micros(1500)), "1.5ms");
+        assert_eq!(human_duration(Duration::from_millis(1234)), "1.23s");
+        assert_eq!(human_duration(Duration::from_secs(123)), "2m03s");
+        assert_eq!(human_duration(Duration::from_secs(3723)), "1h02m03s");
+        assert_eq!(human_duration(Duration::from_secs(90_000)), "25h00m00s");
+        // 四舍五入后进位到下一个单位
+        assert_eq!(human_duration(Duration::from_nanos(999_949)), "999.9µs");
+        assert_eq!(human_duration(Duration::from_nanos(999_950)), "1.0ms");
+        assert_eq!(human_duration(Duration::from_micros(999_960)), "1.00s");
+        assert_eq!(human_duration(Duration::from_millis(59_994)), "59.99s");
+        assert_eq!(human_duration(Duration::from_millis(59_996)), "1m00s");
+        assert_eq!(human_duration(Duration::from_millis(3_599_600)), "1h00m00s");
+    }
+
+    #[test]
+    fn test_human_bytes() {
+        assert_eq!(human_bytes(0), "0 B");
+        assert_eq!(human_bytes(1023), "1023 B");
+        assert_eq!(human_bytes(1024), "1.00 KiB");
+        assert_eq!(human_bytes(1536), "1.50 KiB");
+        assert_eq!(human_bytes(1_290_000), "1.23 MiB");
+        assert_eq!(human_bytes(1024 * 1024 - 1), "1.00 MiB");
+        assert_eq!(human_bytes(5 << 30), "5.00 GiB");
+        assert_eq!(human_bytes(u64::MAX), "16.00 EiB");
+    }
+}
@@ -0,0 +1,259 @@
+use crate::format::{capped_message, is_reserved_key, parse_location, split_target};
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// systemd 日志服务接收原生协议日志的套接字。
+pub(crate) const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// 数据报超过套接字的长度上限时 `send` 返回的错误码（Linux 的 `EMSGSIZE`）。
+const EMSGSIZE: i32 = 90;
+
+/// 该输出端自身写入的日志字段，与之同名的键值对会加上 [`FIELD_PREFIX`] 前缀，避免覆盖这些字段。
+const OWN_FIELDS: [&str; 7] = [
+    "MESSAGE",
+    "PRIORITY",
+    "SYSLOG_IDENTIFIER",
+    "TARGET",
+    "CODE_FILE",
+    "CODE_LINE",
+    "CODE_COLUMN",
+];
+
+/// 与 [`OWN_FIELDS`] 同名的键值对转换为日志字段时添加的前缀。
+const FIELD_PREFIX: &str = "FIELD_";
+
+/// 数据报过大而无法发送时，重新发送的日志内容的长度上限（字节）。
+const FALLBACK_MESSAGE_LEN: usize = 64 * 1024;
+
+/// 通过原生协议将日志发送到 systemd 日志（journald）的输出端。
+///
+/// 每条日志作为一个数据报发送，日志内容、级别、模块名称、调用位置与键值对分别作为独立的日志字段。
+/// 数据报超过套接字的长度上限（通常约为 200 KiB）时，日志内容会被截断为 [`FALLBACK_MESSAGE_LEN`] 字节后重新发送，
+/// 第一次发生时会在标准错误中输出一条警告。
+pub(crate) struct JournaldSink {
+    socket: UnixDatagram,
+    identifier: String,
+    max_record_len: usize,
+}
+
+impl JournaldSink {
+    /// 连接到 `path` 处的日志套接字，日志将以 `identifier` 作为 `SYSLOG_IDENTIFIER`。
+    ///
+    /// `MESSAGE` 字段最多保留 `max_record_len` 字节。
+    pub(crate) fn open(path: &Path, identifier: &str, max_record_len: usize) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(JournaldSink {
+            socket,
+            identifier: identifier.to_string(),
+            max_record_len,
+        })
+    }
+}
+
+impl log::Log for JournaldSink {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        static WARNED: AtomicBool = AtomicBool::new(false);
+
+        let result = self
+            .socket
+            .send(&encode(record, &self.identifier, self.max_record_len));
+        if result.is_err_and(|e| e.raw_os_error() == Some(EMSGSIZE)) {
+            if !WARNED.swap(true, Ordering::Relaxed) {
+                eprintln!(
+                    "CLogger 警告: 一条日志超过了 journald 数据报的长度上限，日志内容已被截断为 {} 字节",
+                    FALLBACK_MESSAGE_LEN
+                );
+            }
+            let limit = self.max_record_len.min(FALLBACK_MESSAGE_LEN);
+            let _ = self.socket.send(&encode(record, &self.identifier, limit));
+        }
+        // 其他发送失败（如日志服务重启）时丢弃该条日志
+    }
+
+    fn flush(&self) {}
+}
+
+/// 将日志级别转换为 syslog 优先级（`Trace` 与 `Debug` 均为 `debug`）。
+fn priority(level: log::Level) -> u8 {
+    match level {
+        log::Level::Error => 3,
+        log::Level::Warn => 4,
+        log::Level::Info => 6,
+        log::Level::Debug | log::Level::Trace => 7,
+    }
+}
+
+/// 按照 journald 原生协议将日志记录编码为一个数据报，日志内容最多保留 `max_record_len` 字节。
+fn encode(record: &log::Record, identifier: &str, max_record_len: usize) -> Vec<u8> {
+    struct Visitor<'a>(&'a mut Vec<u8>);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Visitor<'_> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if !is_reserved_key(key.as_str()) {
+                if let Some(name) = field_name(key.as_str()) {
+                    write_field(self.0, &name, &value.to_string());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut datagram = Vec::new();
+    write_field(
+        &mut datagram,
+        "MESSAGE",
+        &capped_message(record.args(), max_record_len),
+    );
+    write_field(
+        &mut datagram,
+        "PRIORITY",
+        &priority(record.level()).to_string(),
+    );
+    write_field(&mut datagram, "SYSLOG_IDENTIFIER", identifier);
+    let (module, location) = split_target(record.target());
+    write_field(&mut datagram, "TARGET", module);
+    // 优先使用 CLogger 宏记录在目标中的位置信息（包含列号）
+    match location.and_then(parse_location) {
+        Some((file, line, column)) => {
+            write_field(&mut datagram, "CODE_FILE", file);
+            write_field(&mut datagram, "CODE_LINE", &line.to_string());
+            if let Some(column) = column {
+                write_field(&mut datagram, "CODE_COLUMN", &column.to_string());
+            }
+        }
+        None => {
+            if let Some(file) = record.file() {
+                write_field(&mut datagram, "CODE_FILE", file);
+            }
+            if let Some(line) = record.line() {
+                write_field(&mut datagram, "CODE_LINE", &line.to_string());
+            }
+        }
+    }
+    let _ = record.key_values().visit(&mut Visitor(&mut datagram));
+    datagram
+}
+
+/// 将键值对的键转换为合法的日志字段名（大写字母、数字与下划线，不以下划线开头）。
+///
+/// 与该输出端自身写入的字段同名时加上 [`FIELD_PREFIX`] 前缀，如 `message` 对应 `FIELD_MESSAGE`。
+fn field_name(key: &str) -> Option<String> {
+    let name: String = key
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => c.to_ascii_uppercase(),
+            'A'..='Z' | '0'..='9' => c,
+            _ => '_',
+        })
+        .collect();
+    let name = name.trim_start_matches('_');
+    if OWN_FIELDS.contains(&name) {
+        return Some(format!("{}{}", FIELD_PREFIX, name));
+    }
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// 写入一个日志字段。
+///
+/// 不含换行符的值写为 `字段名=值\n`；含有换行符的值写为字段名、换行符、64 位小端序的长度与值本身。
+fn write_field(datagram: &mut Vec<u8>, name: &str, value: &str) {
+    datagram.extend_from_slice(name.as_bytes());
+    if value.contains('\n') {
+        datagram.push(b'\n');
+        datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        datagram.push(b'=');
+    }
+    datagram.extend_from_slice(value.as_bytes());
+    datagram.push(b'\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::DEFAULT_MAX_RECORD_LEN;
+    use log::Log;
+
+    #[test]
+    fn test_journald_sink() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_journal_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = UnixDatagram::bind(&path).unwrap();
+        let sink = JournaldSink::open(&path, "clogger-test", DEFAULT_MAX_RECORD_LEN).unwrap();
+
+        sink.log(
+            &log::Record::builder()
+                .level(log::Level::Warn)
+                .target(r"app::net (src/net.rs:42^7)")
+                .key_values(&[
+                    ("request-id", "abc"),
+                    ("__clogger_color", "1"),
+                    ("priority", "low"),
+                    ("message", "伪造的内容"),
+                ])
+                .args(format_args!("第一行\n第二行"))
+                .build(),
+        );
+
+        let mut buf = [0; 1024];
+        let len = journal.recv(&mut buf).unwrap();
+        let datagram = &buf[..len];
+        let mut message = b"MESSAGE\n".to_vec();
+        message.extend_from_slice(&("第一行\n第二行".len() as u64).to_le_bytes());
+        message.extend_from_slice("第一行\n第二行\n".as_bytes());
+        assert!(datagram.starts_with(&message));
+        let text = String::from_utf8_lossy(datagram);
+        for field in [
+            "PRIORITY=4\n",
+            "SYSLOG_IDENTIFIER=clogger-test\n",
+            "TARGET=app::net\n",
+            "CODE_FILE=src/net.rs\nCODE_LINE=42\nCODE_COLUMN=7\n",
+            "REQUEST_ID=abc\n",
+            // 与输出端自身的字段同名的键值对不会覆盖这些字段
+            "FIELD_PRIORITY=low\n",
+            "FIELD_MESSAGE=伪造的内容\n",
+        ] {
+            assert!(text.contains(field), "缺少字段 {:?}", field);
+        }
+        assert!(!text.contains("CLOGGER_COLOR"));
+        assert_eq!(text.matches("\nPRIORITY=").count(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_journald_oversized() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_journal_big_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = UnixDatagram::bind(&path).unwrap();
+        let sink = JournaldSink::open(&path, "clogger-test", DEFAULT_MAX_RECORD_LEN).unwrap();
+
+        // 超过数据报长度上限的日志被截断后重新发送，而不是被丢弃
+        let message = "a".repeat(DEFAULT_MAX_RECORD_LEN);
+        sink.log(
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .args(format_args!("{}", message))
+                .build(),
+        );
+
+        let mut buf = vec![0; 2 * FALLBACK_MESSAGE_LEN];
+        let len = journal.recv(&mut buf).unwrap();
+        let text = String::from_utf8_lossy(&buf[..len]);
+        assert!(len < FALLBACK_MESSAGE_LEN + 1024, "{}", len);
+        assert!(text.contains("...[truncated]\n"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(feature = "sqlite")]
+use crate::format::{is_reserved_key, FieldMask, MASKED_VALUE};
+use std::fmt::Write;
+
+/// 将 `text` 以 JSON 字符串的形式（包含两侧的引号）写入 `out`。
+pub(crate) fn write_str(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// 日志记录的调用位置，元素为 `(文件, 行号, 列号)`。
+#[cfg(feature = "sqlite")]
+pub(crate) type Location<'a> = (&'a str, u32, Option<u32>);
+
+/// 将日志记录的调用位置、键值对与全局键值对转换为 JSON 对象，`mask` 匹配的键值对的值会被隐藏。
+///
+/// 调用位置排在最前面，`flat_location` 为 `false` 时写为嵌套对象 `"location":{"file":…,"line":…,"column":…}`，
+/// 否则直接写为 `file`、`line` 与 `column` 键，缺少列号时省略 `column`。全局键值对排在最后。
+#[cfg(feature = "sqlite")]
+pub(crate) fn fields_object(
+    record: &log::Record,
+    location: Option<Location>,
+    flat_location: bool,
+    global_fields: &[(String, String)],
+    mask: &FieldMask,
+) -> String {
+    struct Visitor<'a>(&'a mut String, &'a FieldMask);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Visitor<'_> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if !is_reserved_key(key.as_str()) {
+                push_field(self.0, key.as_str(), &value.to_string(), self.1);
+            }
+            Ok(())
+        }
+    }
+
+    let mut object = String::from("{");
+    if let Some(location) = location {
+        if flat_location {
+            push_location(&mut object, location);
+        } else {
+            object.push_str("\"location\":{");
+            push_location(&mut object, location);
+            object.push('}');
+        }
+    }
+    let _ = record.key_values().visit(&mut Visitor(&mut object, mask));
+    for (key, value) in global_fields {
+        push_field(&mut object, key, value, mask);
+    }
+    object.push('}');
+    object
+}
+
+/// 向刚开始的 JSON 对象 `object` 中添加调用位置的 `file`、`line` 与 `column` 键，行号与列号写为数字。
+#[cfg(feature = "sqlite")]
+fn push_location(object: &mut String, (file, line, column): Location) {
+    object.push_str("\"file\":");
+    write_str(object, file);
+    let _ = write!(object, ",\"line\":{}", line);
+    if let Some(column) = column {
+        let _ = write!(object, ",\"column\":{}", column);
+    }
+}
+
+/// 向尚未闭合的 JSON 对象 `object` 中添加一个键值对。
+#[cfg(feature = "sqlite")]
+fn push_field(object: &mut String, key: &str, value: &str, mask: &FieldMask) {
+    if object.len() > 1 {
+        object.push(',');
+    }
+    write_str(object, key);
+    object.push(':');
+    write_str(object, if mask.masks(key) { MASKED_VALUE } else { value });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_fields_location() {
+        let record = log::Record::builder()
+            .key_values(&[("user", "chengcheng")])
+            .build();
+        let location = Some((r"C:\src\main.rs", 42, Some(7)));
+        let mask = FieldMask::default();
+        assert_eq!(
+            fields_object(&record, location, false, &[], &mask),
+            r#"{"location":{"file":"C:\\src\\main.rs","line":42,"column":7},"user":"chengcheng"}"#
+        );
+        assert_eq!(
+            fields_object(&record, location, true, &[], &mask),
+            r#"{"file":"C:\\src\\main.rs","line":42,"column":7,"user":"chengcheng"}"#
+        );
+        // 缺少列号时省略 `column`，没有调用位置时不写入
+        assert_eq!(
+            fields_object(&record, Some(("src/main.rs", 9, None)), true, &[], &mask),
+            r#"{"file":"src/main.rs","line":9,"user":"chengcheng"}"#
+        );
+        assert_eq!(
+            fields_object(&record, None, false, &[], &mask),
+            r#"{"user":"chengcheng"}"#
+        );
+    }
+
+    #[test]
+    fn test_write_str() {
+        let mut out = String::new();
+        write_str(&mut out, "引号\" 反斜杠\\ 换行\n \x1b");
+        assert_eq!(out, r#""引号\" 反斜杠\\ 换行\n \u001b""#);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::meta::write_format_change;
+use log::LevelFilter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// 全局 CLogger 的日志级别。
+static GLOBAL_LEVELS: OnceLock<Arc<SinkLevels>> = OnceLock::new();
+
+/// 可以在运行时调整的日志级别，由输出管线中的过滤函数读取。
+pub(crate) struct SinkLevels {
+    global: AtomicLevel,
+    stdout: AtomicLevel,
+    file: AtomicLevel,
+}
+
+impl SinkLevels {
+    /// 使用初始的全局、标准输出与日志文件级别创建。
+    pub(crate) fn new(global: LevelFilter, stdout: LevelFilter, file: LevelFilter) -> Self {
+        SinkLevels {
+            global: AtomicLevel::new(global),
+            stdout: AtomicLevel::new(stdout),
+            file: AtomicLevel::new(file),
+        }
+    }
+
+    /// 全局日志级别。
+    pub(crate) fn global(&self) -> LevelFilter {
+        self.global.get()
+    }
+
+    /// 标准输出的最低日志级别。
+    pub(crate) fn stdout(&self) -> LevelFilter {
+        self.stdout.get()
+    }
+
+    /// 写入日志文件的最低日志级别。
+    pub(crate) fn file(&self) -> LevelFilter {
+        self.file.get()
+    }
+
+    /// 调整全局日志级别。
+    pub(crate) fn set_global(&self, level: LevelFilter) {
+        self.global.set(level);
+    }
+
+    /// 调整标准输出的最低日志级别。
+    pub(crate) fn set_stdout(&self, level: LevelFilter) {
+        self.stdout.set(level);
+    }
+
+    /// 调整写入日志文件的最低日志级别。
+    pub(crate) fn set_file(&self, level: LevelFilter) {
+        self.file.set(level);
+    }
+}
+
+/// 以原子方式保存的 [`LevelFilter`]。
+struct AtomicLevel(AtomicUsize);
+
+impl AtomicLevel {
+    fn new(level: LevelFilter) -> Self {
+        AtomicLevel(AtomicUsize::new(level as usize))
+    }
+
+    fn get(&self) -> LevelFilter {
+        LevelFilter::iter()
+            .nth(self.0.load(Ordering::Relaxed))
+            .unwrap_or(LevelFilter::Trace)
+    }
+
+    fn set(&self, level: LevelFilter) {
+        self.0.store(level as usize, Ordering::Relaxed);
+    }
+}
+
+/// 记录全局 CLogger 使用的日志级别。
+pub(crate) fn set_global_levels(levels: Arc<SinkLevels>) {
+    let _ = GLOBAL_LEVELS.set(levels);
+}
+
+/// 获取全局 CLogger 使用的日志级别，尚未初始化时返回 `None`。
+pub(crate) fn global_levels() -> Option<&'static SinkLevels> {
+    GLOBAL_LEVELS.get().map(Arc::as_ref)
+}
+
+/// 在运行时调整全局 CLogger 的日志级别。
+///
+/// 全局级别是所有输出端的上限，例如需要通过 [`set_file_level`] 让日志文件记录调试日志时，全局级别也需要至少为 `Debug`。
+/// 各输出端自身的级别不受影响，因此可以在提高全局级别的同时通过 [`set_stdout_level`] 保持终端的输出不变。
+/// 调整后会在日志文件中写入一行以 `{"_clogger_meta":"format_change"` 开头的 JSON 元数据，便于自动处理日志的程序发现变化。
+/// 若 CLogger 尚未初始化，该函数不做任何事。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, set_level};
+/// use log::LevelFilter;
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// set_level(LevelFilter::Debug);
+/// ```
+///
+/// # 参数
+/// - `level`: 新的全局日志级别。
+pub fn set_level(level: LevelFilter) {
+    if let Some(levels) = global_levels() {
+        levels.set_global(level);
+        log::set_max_level(level);
+        write_format_change("level");
+    }
+}
+
+/// 在运行时调整全局 CLogger 输出到标准输出的最低日志级别，其他输出端不受影响。
+///
+/// 若 CLogger 尚未初始化，该函数不做任何事。
+///
+/// # 参数
+/// - `level`: 新的标准输出日志级别。
+pub fn set_stdout_level(level: LevelFilter) {
+    if let Some(levels) = global_levels() {
+        levels.set_stdout(level);
+    }
+}
+
+/// 在运行时调整全局 CLogger 写入日志文件（包括按模块路由的日志文件）的最低日志级别，其他输出端不受影响。
+///
+/// 适合在排查线上问题时只让日志文件记录更详细的日志，而不影响终端的输出。日志文件的级别不会超过全局级别（见 [`set_level`]）。
+/// 与 [`set_level`] 相同，调整后会在日志文件中写入一行 JSON 元数据。若 CLogger 尚未初始化，该函数不做任何事。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, set_file_level, set_level, set_stdout_level};
+/// use log::LevelFilter;
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// // 只让日志文件记录调试日志，终端仍然只输出警告与错误
+/// set_level(LevelFilter::Debug);
+/// set_stdout_level(LevelFilter::Warn);
+/// set_file_level(LevelFilter::Debug);
+/// ```
+///
+/// # 参数
+/// - `level`: 新的日志文件日志级别。
+pub fn set_file_level(level: LevelFilter) {
+    if let Some(levels) = global_levels() {
+        levels.set_file(level);
+        write_format_change("file_level");
+    }
+}
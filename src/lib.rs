@@ -1,8 +1,3 @@
-use chrono::Local;
-use colored::*;
-use fern::Dispatch;
-use std::sync::Once;
-
 /// 用于初始化 CLogger。
 ///
 /// 该函数会初始化 CLogger 并进行一些配置。需要通过 `log_file_path` 参数指定日志文件的保存位置。
@@ -19,39 +14,53 @@ use std::sync::Once;
 /// init_clogger("/dev/null"); // 将 Unix 黑洞设置为日志文件的保存位置，这将不会实际写入任何日志
 /// ```
 ///
+/// CLogger 初始化后会成为全局的 `log` 日志器，因此直接使用 `log` 宏（如第三方库中的 `log::info!`）的日志同样会以
+/// CLogger 的格式输出。这类日志的目标中没有 CLogger 宏生成的位置信息，CLogger 会使用 `log` 记录的文件与行号补全（不包含列号）。
+/// 日志记录中的键值对（如 `log::info!(status = 200; "请求完成")`）会以 ` 键=值` 的形式追加在日志内容之后，
+/// 包含空格等字符的值会被加上引号。
+///
+/// 若设置了非空的 `CLOGGER_FILE` 环境变量，将优先使用环境变量中的路径；同样可以通过 `CLOGGER_LEVEL` 环境变量设置日志级别，
+/// 以便在部署时无需重新编译即可调整配置。详见 [`CloggerBuilder::init`]。
+///
+/// CLogger 只会被初始化一次。重复调用时已有的配置不会改变，CLogger 会输出一条警告说明本次初始化被忽略，
+/// 如果请求的日志文件与正在使用的不同，警告中会一并列出两者，便于发现多处代码各自配置日志的冲突。
+/// 该函数可以在多个线程中同时调用：只有一个线程会完成初始化，其余线程会等待初始化完成后返回，
+/// 因此任何一次调用返回之后输出的日志都不会丢失。
+///
+/// 启用 `auto-init` 特性后，若在初始化之前使用了 CLogger 的日志宏，CLogger 会以默认配置自动初始化，
+/// 日志文件为 `$XDG_STATE_HOME/<程序名称>/app.log`（未设置时为 `~/.local/state/<程序名称>/app.log`，
+/// 均不可用时为临时目录中的 `<程序名称>.log`），并以 `Debug` 级别输出一条说明日志文件位置的日志。
+/// 之后再调用该函数时会像重复初始化一样被忽略，因此需要自定义配置的程序应在输出第一条日志之前完成初始化。
+///
 /// # 参数
 /// - `log_file_path`: 日志文件的保存位置。
 pub fn init_clogger(log_file_path: &str) {
-    static INIT: Once = Once::new();
-    INIT.call_once(|| {
-        let base_config = Dispatch::new()
-            .format(|out, message, record| {
-                let timestamp = Local::now()
-                    .format("%Y-%m-%d %H:%M:%S%.3f")
-                    .to_string()
-                    .cyan();
-                let level = match record.level() {
-                    log::Level::Info => "I".to_string().green(), // 普通日志为绿色
-                    log::Level::Warn => "W".to_string().yellow(), // 警告日志为黄色
-                    log::Level::Error => "E".to_string().red(),  // 错误日志为红色
-                    log::Level::Debug => "D".to_string().blue(), // 调试日志为蓝色
-                    log::Level::Trace => "T".to_string().purple(), // 追踪日志为紫色
-                };
-                out.finish(format_args!(
-                    "({}) [{}] [{}] {}",
-                    timestamp,
-                    level,
-                    record.target().magenta(),
-                    message
-                ))
-            })
-            .level(log::LevelFilter::Debug)
-            .chain(std::io::stdout()) // 输出到终端
-            .chain(fern::log_file(log_file_path).unwrap()); // 写入日志文件
-
-        base_config.apply().unwrap();
-        c_log!("CLogger 初始化完成 (ง •_•)ง");
-    });
+    CloggerBuilder::new().log_file(log_file_path).init();
+}
+
+/// 用于以预先打开的文件初始化 CLogger。
+///
+/// 与 [`init_clogger`] 相同，但日志写入传入的文件，CLogger 自身不会打开任何路径。适合由 systemd 等进程管理器
+/// 传入文件描述符，或受 seccomp 限制禁止调用 `open` 的沙箱环境。详见 [`CloggerBuilder::log_file_handle`]。
+///
+/// # 示例
+/// ```rust
+/// use clogger::init_clogger_with_file;
+/// use std::fs::OpenOptions;
+///
+/// // 在 Unix 上也可以通过 `std::os::fd::FromRawFd` 使用继承的文件描述符
+/// let file = OpenOptions::new()
+///     .create(true)
+///     .append(true)
+///     .open("/tmp/clogger_example.log")
+///     .unwrap();
+/// init_clogger_with_file(file);
+/// ```
+///
+/// # 参数
+/// - `file`: 已打开的可写文件。
+pub fn init_clogger_with_file(file: std::fs::File) {
+    CloggerBuilder::new().log_file_handle(file).init();
 }
 
 /// 用于输出和记录常规日志。
@@ -69,22 +78,47 @@ pub fn init_clogger(log_file_path: &str) {
 /// c_log!("example::moudle_name", "这是一条常规日志！(づ｡◕‿‿◕｡)づ");
 /// ```
 ///
+/// 模块名称已经是一个不再使用的 `String` 时，可以在前面加上 `move`，位置信息会直接追加到该字符串后面，
+/// 省去一次字符串分配：
+/// ```rust
+/// use clogger::{init_clogger, c_log};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 输出日志
+/// let tenant = "acme";
+/// c_log!(move format!("example::tenant::{}", tenant), "这是一条常规日志！");
+/// ```
+///
 /// # 参数
-/// - `$module` (可选): 模块名称。
+/// - `$logger` (可选): 写入的 [`Clogger`] 实例，以 `logger =>` 的形式传入。未指定时写入全局的 CLogger。
+/// - `$module` (可选): 模块名称。加上 `move` 前缀时会取得 `String` 的所有权并复用其内存。
 /// - `$message`: 日志信息内容。
 #[macro_export]
 macro_rules! c_log {
+    (move $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::info!(target: $crate::__private::macro_target_owned($module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_str(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
+        }
+    };
+    ($logger:expr => $message:expr) => {
+        $logger.log(::std::module_path!(), $message)
+    };
+    ($logger:expr => $module:expr, $message:expr) => {
+        $logger.log($module, $message)
+    };
     ($message:expr) => {
         {
-            c_log!(module_path!(), $message);
+            $crate::c_log!(::std::module_path!(), $message);
         }
     };
     ($module:expr, $message:expr) => {
         {
-            use log::info;
-            use std::panic::Location;
-            let location = Location::caller();
-            info!(target: format!("{} ({}:{})", $module, location.file(), location.line()).as_str(), "{}", $message);
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::info!(target: $crate::__private::macro_target(&$module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_ref(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
         }
     };
 }
@@ -105,22 +139,34 @@ macro_rules! c_log {
 /// ```
 ///
 /// # 参数
-/// - `$module` (可选): 模块名称。
+/// - `$logger` (可选): 写入的 [`Clogger`] 实例，以 `logger =>` 的形式传入。未指定时写入全局的 CLogger。
+/// - `$module` (可选): 模块名称。加上 `move` 前缀时会取得 `String` 的所有权并复用其内存，参见 [`c_log!`]。
 /// - `$message`: 日志信息内容。
 #[macro_export]
 macro_rules! c_warn {
+    (move $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::warn!(target: $crate::__private::macro_target_owned($module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_str(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
+        }
+    };
+    ($logger:expr => $message:expr) => {
+        $logger.warn(::std::module_path!(), $message)
+    };
+    ($logger:expr => $module:expr, $message:expr) => {
+        $logger.warn($module, $message)
+    };
     ($message:expr) => {
         {
-            c_warn!(module_path!(), $message);
+            $crate::c_warn!(::std::module_path!(), $message);
         }
     };
     ($module:expr, $message:expr) => {
         {
-            use log::warn;
-            use colored::Colorize;
-            use std::panic::Location;
-            let location = Location::caller();
-            warn!(target: format!("{} ({}:{}^{})", $module, location.file(), location.line(), location.column()).as_str(), "{}", $message.yellow());
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::warn!(target: $crate::__private::macro_target(&$module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_ref(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
         }
     };
 }
@@ -141,22 +187,34 @@ macro_rules! c_warn {
 /// ```
 ///
 /// # 参数
-/// - `$module` (可选): 模块名称。
+/// - `$logger` (可选): 写入的 [`Clogger`] 实例，以 `logger =>` 的形式传入。未指定时写入全局的 CLogger。
+/// - `$module` (可选): 模块名称。加上 `move` 前缀时会取得 `String` 的所有权并复用其内存，参见 [`c_log!`]。
 /// - `$message`: 日志信息内容。
 #[macro_export]
 macro_rules! c_error {
+    (move $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::error!(target: $crate::__private::macro_target_owned($module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_str(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
+        }
+    };
+    ($logger:expr => $message:expr) => {
+        $logger.error(::std::module_path!(), $message)
+    };
+    ($logger:expr => $module:expr, $message:expr) => {
+        $logger.error($module, $message)
+    };
     ($message:expr) => {
         {
-            c_error!(module_path!(), $message);
+            $crate::c_error!(::std::module_path!(), $message);
         }
     };
     ($module:expr, $message:expr) => {
         {
-            use log::error;
-            use colored::Colorize;
-            use std::panic::Location;
-            let location = Location::caller();
-            error!(target: format!("{} ({}:{}^{})", $module, location.file(), location.line(), location.column()).as_str(), "{}", $message.red());
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::error!(target: $crate::__private::macro_target(&$module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_ref(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
         }
     };
 }
@@ -177,34 +235,899 @@ macro_rules! c_error {
 /// ```
 ///
 /// # 参数
-/// - `$module` (可选): 模块名称（用于在日志中标记日志来源）。
+/// - `$logger` (可选): 写入的 [`Clogger`] 实例，以 `logger =>` 的形式传入。未指定时写入全局的 CLogger。
+/// - `$module` (可选): 模块名称（用于在日志中标记日志来源）。加上 `move` 前缀时会取得 `String` 的所有权并复用其内存，参见 [`c_log!`]。
 /// - `$message`: 日志信息内容。
 #[macro_export]
 macro_rules! c_debug {
+    (move $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::debug!(target: $crate::__private::macro_target_owned($module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_str(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
+        }
+    };
+    ($logger:expr => $message:expr) => {
+        $logger.debug(::std::module_path!(), $message)
+    };
+    ($logger:expr => $module:expr, $message:expr) => {
+        $logger.debug($module, $message)
+    };
     ($message:expr) => {
         {
-            c_debug!(module_path!(), $message);
+            $crate::c_debug!(::std::module_path!(), $message);
         }
     };
     ($module:expr, $message:expr) => {
         {
-            use log::debug;
-            use std::panic::Location;
-            let location = Location::caller();
-            debug!(target: format!("{} ({}:{}^{})", $module, location.file(), location.line(), location.column()).as_str(), "{}", $message);
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::debug!(target: $crate::__private::macro_target(&$module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_ref(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
+        }
+    };
+}
+
+/// 用于以指定的级别输出和记录日志。
+///
+/// 该宏会将日志信息输出到终端并写入日志文件，日志的级别由 `$level` 参数在运行时决定，
+/// 适合严重程度取决于数据的场景（例如根据 HTTP 状态码决定日志级别）。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_log_at};
+/// use log::Level;
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 根据状态码决定日志级别
+/// let status = 503;
+/// let level = if status >= 500 { Level::Error } else { Level::Info };
+/// c_log_at!(level, "example::moudle_name", format!("请求完成，状态码为 {}", status));
+/// ```
+///
+/// 指定的 `$module` 会完整地替换 `module_path!()`，而位置信息始终是宏的调用位置。在封装日志的辅助函数中，
+/// 可以为函数加上 `#[track_caller]`，此时位置信息会指向辅助函数的调用方而不是函数内部；
+/// 模块名称无法通过 `#[track_caller]` 传递，需要由调用方传入 `module_path!()`：
+/// ```rust
+/// use clogger::{init_clogger, c_log_at};
+/// use log::Level;
+///
+/// #[track_caller]
+/// fn report(module: &str, status: u16) {
+///     let level = if status >= 500 { Level::Error } else { Level::Info };
+///     c_log_at!(level, module, format!("请求完成，状态码为 {}", status));
+/// }
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 日志的模块名称与位置信息均为这一行，而不是 `report` 函数内部
+/// report(module_path!(), 503);
+/// ```
+///
+/// # 参数
+/// - `$level`: 日志级别（`log::Level`）。
+/// - `$module` (可选): 模块名称。加上 `move` 前缀时会取得 `String` 的所有权并复用其内存，参见 [`c_log!`]。
+/// - `$message`: 日志信息内容。
+#[macro_export]
+macro_rules! c_log_at {
+    ($level:expr, move $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::log!(target: $crate::__private::macro_target_owned($module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_str(), $level, __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
+        }
+    };
+    ($level:expr, $message:expr) => {
+        {
+            $crate::c_log_at!($level, ::std::module_path!(), $message);
+        }
+    };
+    ($level:expr, $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::log!(target: $crate::__private::macro_target(&$module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_ref(), $level, __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"); "{}", $message);
+        }
+    };
+}
+
+/// 用于在重试等场景中，根据是否为最后一次尝试决定以错误或警告级别输出和记录日志。
+///
+/// `$is_final` 为 `true` 时日志的级别为 `Error`，否则为 `Warn`，调用位置与 [`c_error!`] 相同。
+/// 这样可以将预期中的临时失败记录为警告，只有最终的失败才记录为错误。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_error_or_warn};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// let attempts = 3;
+/// for attempt in 1..=attempts {
+///     // 前两次失败记录为警告，最后一次失败记录为错误
+///     c_error_or_warn!(attempt == attempts, "example::moudle_name", format!("第 {} 次连接失败", attempt));
+/// }
+/// ```
+///
+/// # 参数
+/// - `$is_final`: 是否为最后一次尝试（`bool`）。
+/// - `$module` (可选): 模块名称。
+/// - `$message`: 日志信息内容。
+#[macro_export]
+macro_rules! c_error_or_warn {
+    ($is_final:expr, $message:expr) => {{
+        $crate::c_error_or_warn!($is_final, ::std::module_path!(), $message);
+    }};
+    ($is_final:expr, $module:expr, $message:expr) => {{
+        let level = if $is_final {
+            $crate::__private::log::Level::Error
+        } else {
+            $crate::__private::log::Level::Warn
+        };
+        $crate::c_log_at!(level, $module, $message);
+    }};
+}
+
+/// 用于在性能敏感的代码中输出和记录常规日志。
+///
+/// 与 [`c_log!`] 不同，该宏不会获取调用位置，也不会为每条日志构建包含位置信息的目标字符串，
+/// 而是直接以 `module_path!()` 作为目标转发给 `log::info!`，日志的级别为 `Info`，同样遵循日志级别的过滤。
+/// 输出时 CLogger 仍会使用 `log` 记录的文件与行号补全位置信息（不包含列号）。
+/// 与其他日志宏相同，启用 `auto-init` 特性时会在 CLogger 尚未初始化时自动完成初始化。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_fast};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// for _ in 0..3 {
+///     c_fast!("处理了一个数据包");
+/// }
+/// ```
+///
+/// # 参数
+/// - `$message`: 日志信息内容。
+#[macro_export]
+macro_rules! c_fast {
+    ($message:expr) => {{
+        $crate::__private::auto_init();
+        $crate::__private::log::info!(target: ::std::module_path!(), "{}", $message)
+    }};
+}
+
+/// 用于以指定的时间戳输出和记录常规日志。
+///
+/// 该宏与 [`c_log!`] 相同，日志的级别为 `Info`，但日志的时间戳使用 `$time` 而不是当前时间，
+/// 适合回放历史事件，使日志中的时间反映事件实际发生的时间。`$time` 可以是任意时区的 `chrono::DateTime`，
+/// 输出时会按照 [`TimestampMode`] 转换为对应的时区。若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use clogger::{init_clogger, c_log_at_time};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 回放一条历史事件
+/// let time = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+/// c_log_at_time!(time, "example::moudle_name", "订单已创建");
+/// ```
+///
+/// # 参数
+/// - `$time`: 日志的时间戳（`chrono::DateTime`）。
+/// - `$module` (可选): 模块名称。
+/// - `$message`: 日志信息内容。
+#[macro_export]
+macro_rules! c_log_at_time {
+    ($time:expr, $message:expr) => {
+        {
+            $crate::c_log_at_time!($time, ::std::module_path!(), $message);
+        }
+    };
+    ($time:expr, $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::info!(target: $crate::__private::macro_target(&$module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_ref(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"), __clogger_timestamp = ($time).timestamp_micros(); "{}", $message);
+        }
+    };
+}
+
+/// 用于输出和记录使用指定颜色显示的常规日志。
+///
+/// 该宏与 [`c_log!`] 相同，日志的级别为 `Info`，但终端中的日志信息内容会使用 `$color` 着色（例如醒目的成功提示），
+/// 不影响其他日志按级别着色，写入日志文件的内容仍然不带颜色。若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_log_colored, Color};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 使用亮绿色显示部署成功的提示
+/// c_log_colored!(Color::BrightGreen, "example::moudle_name", "部署成功！ヽ(✿ﾟ▽ﾟ)ノ");
+/// ```
+///
+/// # 参数
+/// - `$color`: 日志信息内容的颜色（[`Color`]）。
+/// - `$module` (可选): 模块名称。
+/// - `$message`: 日志信息内容。
+#[macro_export]
+macro_rules! c_log_colored {
+    ($color:expr, $message:expr) => {
+        {
+            $crate::c_log_colored!($color, ::std::module_path!(), $message);
+        }
+    };
+    ($color:expr, $module:expr, $message:expr) => {
+        {
+            $crate::__private::auto_init();
+            let location = ::std::panic::Location::caller();
+            $crate::__private::log::info!(target: $crate::__private::macro_target(&$module, location, ::std::option_env!("CARGO_MANIFEST_DIR")).as_ref(), __clogger_crate_version = ::std::env!("CARGO_PKG_VERSION"), __clogger_color = $crate::__private::encode_color($color); "{}", $message);
+        }
+    };
+}
+
+/// 用于在循环中定期输出和记录进度（心跳）日志。
+///
+/// 每次调用时只有距离上一次心跳已经过了 `$interval` 才会调用 `$message` 闭包生成日志内容并以 `Info` 级别输出，
+/// 其余时候不会调用闭包，因此在频繁执行的循环中几乎没有开销。间隔按调用位置分别计算（同一位置的多个线程共享），
+/// 第一次执行到该位置时开始计时。若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_heartbeat};
+/// use std::time::Duration;
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// let mut count = 0;
+/// for _ in 0..100_000 {
+///     count += 1;
+///     // 最多每 10 秒输出一次进度
+///     c_heartbeat!(Duration::from_secs(10), "example::moudle_name", || format!("已处理 {} 条数据", count));
+/// }
+/// ```
+///
+/// # 参数
+/// - `$interval`: 两次心跳之间的最短间隔（`std::time::Duration`）。
+/// - `$module` (可选): 模块名称。
+/// - `$message`: 生成日志信息内容的闭包。
+#[macro_export]
+macro_rules! c_heartbeat {
+    ($interval:expr, $message:expr $(,)?) => {
+        $crate::c_heartbeat!($interval, ::std::module_path!(), $message)
+    };
+    ($interval:expr, $module:expr, $message:expr $(,)?) => {{
+        static HEARTBEAT: $crate::__private::Heartbeat = $crate::__private::Heartbeat::new();
+        if HEARTBEAT.ready($interval) {
+            $crate::c_log!($module, ($message)());
         }
+    }};
+}
+
+/// 用于记录作用域的进入与离开。
+///
+/// 该宏会返回一个 [`ScopeGuard`]，创建时以 `Debug` 级别输出 `> $label`，在守卫被丢弃（离开作用域）时输出
+/// `< $label (耗时)`。同一线程中嵌套的作用域会按层数缩进，便于理解嵌套的调用流程。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_scope};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 记录作用域
+/// let _guard = c_scope!("example::moudle_name", "处理请求");
+/// {
+///     let _inner = c_scope!("example::moudle_name", "读取数据库"); // 该作用域会被缩进
+/// }
+/// ```
+///
+/// # 参数
+/// - `$module` (可选): 模块名称。
+/// - `$label`: 作用域的标签。
+#[macro_export]
+macro_rules! c_scope {
+    ($label:expr) => {
+        $crate::c_scope!(::std::module_path!(), $label)
     };
+    ($module:expr, $label:expr) => {{
+        let location = ::std::panic::Location::caller();
+        $crate::ScopeGuard::new(
+            $crate::__private::macro_target(
+                &$module,
+                location,
+                ::std::option_env!("CARGO_MANIFEST_DIR"),
+            )
+            .into_owned(),
+            $label.to_string(),
+        )
+    }};
 }
 
+/// 用于输出表达式的值并将其返回，类似于标准库的 `dbg!`。
+///
+/// 该宏会以 `Debug` 级别输出 `表达式 = 值`（值使用 `{:?}` 格式化），并原样返回表达式的值，因此可以直接插入到表达式中间。
+/// 与 `dbg!` 不同，输出会经过 CLogger 写入日志文件，并遵循日志级别的过滤；级别被过滤时不会格式化表达式的值。
+/// 模块名称使用 `module_path!()` 自动获取。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_dbg};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 输出 `a * 2 = 42`，并将结果用于后续的计算
+/// let a = 21;
+/// let b = c_dbg!(a * 2) + 1;
+/// assert_eq!(b, 43);
+/// ```
+///
+/// # 参数
+/// - `$value`: 需要输出的表达式，需要实现 `Debug`。
+#[macro_export]
+macro_rules! c_dbg {
+    ($value:expr $(,)?) => {
+        match $value {
+            value => {
+                if $crate::__private::log::log_enabled!($crate::__private::log::Level::Debug) {
+                    $crate::c_debug!(
+                        ::std::module_path!(),
+                        ::std::format!("{} = {:?}", ::std::stringify!($value), &value)
+                    );
+                }
+                value
+            }
+        }
+    };
+}
+
+/// 用于统计代码块中的内存分配并将结果输出（需要启用 `alloc-count` 特性）。
+///
+/// 该宏会执行 `$block` 并返回其值，同时以 `Debug` 级别输出 `标签: N 次分配，共 M`（包含位置信息，大小 `M` 以 [`human_bytes`] 的格式输出），
+/// 其中 `realloc` 也计为一次分配（字节数只计入增加的部分），只统计当前线程中的分配。统计依赖 [`CountingAllocator`]，
+/// 需要先通过 `#[global_allocator]` 将其设置为全局分配器，否则日志中会说明无法统计。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_alloc, CountingAllocator};
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 输出 `构建列表: 1 次分配，共 400 B`
+/// let list = c_alloc!("example::moudle_name", "构建列表", {
+///     let mut list = Vec::with_capacity(100);
+///     list.extend(0u32..100);
+///     list
+/// });
+/// assert_eq!(list.len(), 100);
+/// ```
+///
+/// # 参数
+/// - `$module` (可选): 模块名称。
+/// - `$label`: 代码块的标签。
+/// - `$block`: 需要统计的代码块。
+#[cfg(feature = "alloc-count")]
+#[macro_export]
+macro_rules! c_alloc {
+    ($label:expr, $block:expr $(,)?) => {
+        $crate::c_alloc!(::std::module_path!(), $label, $block)
+    };
+    ($module:expr, $label:expr, $block:expr $(,)?) => {{
+        let snapshot = $crate::__private::AllocSnapshot::now();
+        let value = $block;
+        if $crate::__private::log::log_enabled!($crate::__private::log::Level::Debug) {
+            $crate::c_debug!($module, snapshot.describe_since(&$label));
+        }
+        value
+    }};
+}
+
+/// 用于输出值占用的内存大小并将其返回。
+///
+/// 该宏会以 `Debug` 级别输出 `size_of_val(表达式) = 大小`（包含位置信息），大小由 `std::mem::size_of_val` 计算
+/// 并以 [`human_bytes`] 的格式输出，
+/// 只包含值本身（如 `Vec` 的指针、长度与容量），不包含其指向的堆内存。与 [`c_dbg!`] 相同，宏会原样返回表达式的值，
+/// 因此可以直接插入到表达式中间；未启用 `Debug` 级别时不会输出任何内容。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_sizeof};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 输出 `size_of_val([0u64; 128]) = 1.00 KiB`
+/// let buffer = c_sizeof!("example::moudle_name", [0u64; 128]);
+/// assert_eq!(buffer.len(), 128);
+/// ```
+///
+/// # 参数
+/// - `$module` (可选): 模块名称。
+/// - `$value`: 需要计算大小的表达式。
+#[macro_export]
+macro_rules! c_sizeof {
+    ($value:expr $(,)?) => {
+        $crate::c_sizeof!(::std::module_path!(), $value)
+    };
+    ($module:expr, $value:expr $(,)?) => {
+        match $value {
+            value => {
+                if $crate::__private::log::log_enabled!($crate::__private::log::Level::Debug) {
+                    $crate::c_debug!(
+                        $module,
+                        ::std::format!(
+                            "size_of_val({}) = {}",
+                            ::std::stringify!($value),
+                            $crate::human_bytes(::std::mem::size_of_val(&value) as u64)
+                        )
+                    );
+                }
+                value
+            }
+        }
+    };
+}
+
+/// 用于在函数入口处记录参数的值。
+///
+/// 该宏会以 `Trace` 级别输出 `enter: a=1 b="x"` 形式的日志（包含位置信息），参数名通过 `stringify!` 获取，
+/// 参数值使用 `{:?}` 格式化，因此参数需要实现 `Debug`。未启用 `Trace` 级别时不会格式化任何参数，几乎没有开销。
+/// 可以与 [`c_scope!`] 配合使用：前者记录函数的输入，后者记录函数的耗时。
+/// 指定模块名称时，`$module` 需要是字符串字面量；未指定时将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_enter};
+///
+/// fn transfer(from: u32, to: u32, amount: i64) {
+///     c_enter!("example::moudle_name", from, to, amount); // 输出 `enter: from=1 to=2 amount=100`
+///     // ...
+/// }
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// transfer(1, 2, 100);
+/// ```
+///
+/// # 参数
+/// - `$module` (可选): 模块名称（字符串字面量）。
+/// - `$arg`: 需要记录的参数，可以有任意多个。
+#[macro_export]
+macro_rules! c_enter {
+    (@target $module:expr $(, $arg:expr)*) => {
+        if $crate::__private::log::log_enabled!($crate::__private::log::Level::Trace) {
+            #[allow(unused_mut)]
+            let mut message = ::std::string::String::from("enter:");
+            $(
+                ::std::fmt::Write::write_fmt(
+                    &mut message,
+                    ::std::format_args!(" {}={:?}", ::std::stringify!($arg), &$arg),
+                )
+                .unwrap();
+            )*
+            $crate::c_log_at!($crate::__private::log::Level::Trace, $module, message);
+        }
+    };
+    ($module:literal $(, $arg:expr)* $(,)?) => {
+        $crate::c_enter!(@target $module $(, $arg)*)
+    };
+    ($($arg:expr),* $(,)?) => {
+        $crate::c_enter!(@target ::std::module_path!() $(, $arg)*)
+    };
+}
+
+/// 用于在断言失败时记录错误日志。
+///
+/// 与 `assert!` 相同，`$condition` 为 `false` 时会引发 panic；不同的是，panic 之前会先以 `Error` 级别输出并记录
+/// 断言的条件与说明（包含位置信息），并刷新所有输出端，确保断言失败的原因被写入日志文件，便于事后分析。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust,should_panic
+/// use clogger::{init_clogger, c_assert};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// // 断言失败时记录错误日志并 panic
+/// let balance = -1;
+/// c_assert!(balance >= 0, "example::moudle_name", "账户余额不能为负数");
+/// ```
+///
+/// # 参数
+/// - `$condition`: 断言的条件。
+/// - `$module` (可选): 模块名称。
+/// - `$message`: 断言的说明。
+#[macro_export]
+macro_rules! c_assert {
+    ($condition:expr, $message:expr) => {
+        $crate::c_assert!($condition, ::std::module_path!(), $message)
+    };
+    ($condition:expr, $module:expr, $message:expr) => {
+        if !$condition {
+            let message = ::std::format!(
+                "断言失败: `{}`: {}",
+                ::std::stringify!($condition),
+                $message
+            );
+            $crate::c_error!($module, &message);
+            $crate::flush_clogger();
+            ::std::panic!("{}", message);
+        }
+    };
+}
+
+/// 用于取出 `Option` 或 `Result` 中的值，失败时先记录错误日志再 panic，类似于 `.expect()`。
+///
+/// `$value` 为 `Some` 或 `Ok` 时，宏的值即为其中的值；为 `None` 或 `Err` 时，会以 `Error` 级别输出并记录 `$message`
+/// （`Err` 时附加错误的 `Debug` 内容，与 `.expect()` 相同），并刷新所有输出端，然后以相同的内容引发 panic，
+/// 确保失败的原因在程序终止之前被写入日志文件。若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust,should_panic
+/// use clogger::{init_clogger, c_expect};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// let port: u16 = c_expect!("8080".parse::<u16>(), "example::moudle_name", "端口号无效");
+/// // 记录 `缺少配置项 host` 后 panic
+/// let host: &str = c_expect!(None, "example::moudle_name", "缺少配置项 host");
+/// ```
+///
+/// # 参数
+/// - `$value`: 需要取出值的 `Option<T>` 或 `Result<T, E>`（`E` 需要实现 `Debug`）。
+/// - `$module` (可选): 模块名称。
+/// - `$message`: 失败时的说明。
+#[macro_export]
+macro_rules! c_expect {
+    ($value:expr, $message:expr $(,)?) => {
+        $crate::c_expect!($value, ::std::module_path!(), $message)
+    };
+    ($value:expr, $module:expr, $message:expr $(,)?) => {
+        match $crate::__private::Expect::into_expected($value) {
+            ::std::result::Result::Ok(value) => value,
+            ::std::result::Result::Err(error) => {
+                let message = match error {
+                    ::std::option::Option::Some(error) => ::std::format!("{}: {}", $message, error),
+                    ::std::option::Option::None => ::std::string::ToString::to_string(&$message),
+                };
+                $crate::c_error!($module, &message);
+                $crate::flush_clogger();
+                ::std::panic!("{}", message);
+            }
+        }
+    };
+}
+
+/// 仅在调试构建中生效的 [`c_assert!`]。
+///
+/// 与 `debug_assert!` 相同，未启用 `debug_assertions`（如 release 构建）时不会检查 `$condition`。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_debug_assert};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// let retries = 3;
+/// c_debug_assert!(retries <= 5, "example::moudle_name", "重试次数超出上限");
+/// ```
+///
+/// # 参数
+/// - `$condition`: 断言的条件。
+/// - `$module` (可选): 模块名称。
+/// - `$message`: 断言的说明。
+#[macro_export]
+macro_rules! c_debug_assert {
+    ($($arg:tt)*) => {
+        if ::std::cfg!(debug_assertions) {
+            $crate::c_assert!($($arg)*);
+        }
+    };
+}
+
+/// 用于在操作失败时记录错误日志，并原样返回操作的结果。
+///
+/// `$result` 为 `Err` 时，该宏会以 `Error` 级别输出并记录 `$context: 错误: 原因: ...` 形式的日志，
+/// 其中包含错误的 `Display` 内容与完整的 `source()` 链；为 `Ok` 时不输出任何日志。
+/// 宏的值即为 `$result` 本身，因此可以与 `?` 组合使用，将“记录后向上传递”的写法集中在一处。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_try};
+///
+/// fn load_config(path: &str) -> std::io::Result<String> {
+///     // 读取失败时记录错误日志，然后将错误交给调用方处理
+///     let config = c_try!("example::moudle_name", "加载配置文件", std::fs::read_to_string(path))?;
+///     Ok(config)
+/// }
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// assert!(load_config("/不存在的路径/config.toml").is_err());
+/// ```
+///
+/// # 参数
+/// - `$module` (可选): 模块名称。
+/// - `$context`: 操作的说明。
+/// - `$result`: 操作的结果（`Result<T, E>`，`E` 需要实现 `std::error::Error`）。
+#[macro_export]
+macro_rules! c_try {
+    ($context:expr, $result:expr $(,)?) => {
+        $crate::c_try!(::std::module_path!(), $context, $result)
+    };
+    ($module:expr, $context:expr, $result:expr $(,)?) => {
+        match $result {
+            ::std::result::Result::Ok(value) => ::std::result::Result::Ok(value),
+            ::std::result::Result::Err(error) => {
+                $crate::c_error!($module, $crate::__private::error_chain(&$context, &error));
+                ::std::result::Result::Err(error)
+            }
+        }
+    };
+}
+
+/// 用于输出和记录 `anyhow::Error`，包括完整的上下文链与堆栈回溯（需要启用 `anyhow` 特性）。
+///
+/// 该宏会以 `Error` 级别输出并记录错误，调用位置与 [`c_error!`] 相同。日志的第一行为最外层的错误，
+/// 之后每个上下文（由外到内）各占一行并缩进；设置了 `RUST_BACKTRACE` 或 `RUST_LIB_BACKTRACE` 环境变量、
+/// 错误捕获了堆栈回溯时，会在最后附加堆栈回溯。`$error` 以引用的方式使用，调用后仍然可以继续使用。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use anyhow::Context;
+/// use clogger::{init_clogger, c_anyhow};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// let error = std::fs::read_to_string("/不存在的路径/config.toml")
+///     .context("无法加载配置")
+///     .unwrap_err();
+/// // 输出 `无法加载配置` 与缩进的 `原因: No such file or directory (os error 2)`
+/// c_anyhow!("example::moudle_name", error);
+/// ```
+///
+/// # 参数
+/// - `$module` (可选): 模块名称。
+/// - `$error`: 需要记录的错误（`anyhow::Error`）。
+#[cfg(feature = "anyhow")]
+#[macro_export]
+macro_rules! c_anyhow {
+    ($error:expr $(,)?) => {
+        $crate::c_anyhow!(::std::module_path!(), $error)
+    };
+    ($module:expr, $error:expr $(,)?) => {
+        $crate::c_error!($module, $crate::__private::anyhow_report(&$error))
+    };
+}
+
+/// 用于以 base64 编码输出和记录二进制数据（需要启用 `base64` 特性）。
+///
+/// 该宏会以 `Debug` 级别输出 `base64 (N 字节): 编码后的内容` 形式的日志，适合记录原始的协议数据以便之后解码分析。
+/// 未启用 `Debug` 级别时不会进行编码，几乎没有开销。`$bytes` 可以是任何实现了 `AsRef<[u8]>` 的值。
+/// 若未指定 `$module` 参数，将使用 `module_path!()` 自动获取模块名称。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_base64};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// let packet = [0x16, 0x03, 0x01, 0x00, 0xa5];
+/// c_base64!("example::moudle_name", &packet); // 输出 `base64 (5 字节): FgMBAKU=`
+/// ```
+///
+/// # 参数
+/// - `$module` (可选): 模块名称。
+/// - `$bytes`: 需要记录的二进制数据。
+#[cfg(feature = "base64")]
+#[macro_export]
+macro_rules! c_base64 {
+    ($bytes:expr $(,)?) => {
+        $crate::c_base64!(::std::module_path!(), $bytes)
+    };
+    ($module:expr, $bytes:expr $(,)?) => {
+        if $crate::__private::log::log_enabled!($crate::__private::log::Level::Debug) {
+            match &$bytes {
+                bytes => {
+                    let bytes: &[u8] = ::std::convert::AsRef::<[u8]>::as_ref(bytes);
+                    $crate::c_debug!(
+                        $module,
+                        ::std::format!(
+                            "base64 ({} 字节): {}",
+                            bytes.len(),
+                            $crate::__private::base64_encode(bytes)
+                        )
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// 用于缩进一组相关的日志。
+///
+/// 该宏会返回一个 [`GroupGuard`]，守卫存在期间当前线程输出的所有日志都会增加一层缩进，嵌套的分组会继续缩进，
+/// 便于阅读层层嵌套的流程。与 [`c_scope!`] 不同，该宏只影响日志的格式，不会计时，也不会输出进入与离开的日志；
+/// 两者可以组合使用，分组中的作用域日志同样会被缩进。`$label` 仅用于在代码中说明分组的用途。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_group, c_log};
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// c_log!("example::moudle_name", "开始部署");
+/// {
+///     let _group = c_group!("部署步骤");
+///     c_log!("example::moudle_name", "上传文件"); // 该日志会被缩进
+///     c_log!("example::moudle_name", "重启服务"); // 该日志会被缩进
+/// }
+/// c_log!("example::moudle_name", "部署完成");
+/// ```
+///
+/// # 参数
+/// - `$label`: 分组的标签。
+#[macro_export]
+macro_rules! c_group {
+    ($label:expr) => {{
+        let _ = $label;
+        $crate::GroupGuard::new()
+    }};
+}
+
+/// 用于临时提高当前线程的日志级别。
+///
+/// 该宏会返回一个 [`VerboseGuard`]，守卫存在期间当前线程的日志级别至少为 `$level`，离开作用域时恢复。
+/// 只有当前线程受影响，适合在不影响整个进程的情况下，为一段可疑的代码输出详细的调试日志。
+/// 各输出端自身的级别（如 [`CloggerBuilder::stdout_level`]）仍然有效。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{init_clogger, c_debug, c_verbose_scope};
+/// use log::LevelFilter;
+///
+/// // 初始化 CLogger
+/// init_clogger("/tmp/clogger_example.log");
+/// {
+///     let _verbose = c_verbose_scope!(LevelFilter::Trace);
+///     c_debug!("example::moudle_name", "这条调试输出会被记录"); // 即使全局级别为 `Info`
+/// }
+/// ```
+///
+/// # 参数
+/// - `$level`: 守卫存在期间的最低日志级别（`log::LevelFilter`）。
+#[macro_export]
+macro_rules! c_verbose_scope {
+    ($level:expr) => {
+        $crate::VerboseGuard::new($level)
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    //! 供宏展开使用的依赖项，不属于公开 API。
+    #[cfg(feature = "alloc-count")]
+    pub use crate::alloc::AllocSnapshot;
+    pub use crate::auto_init::auto_init;
+    pub use crate::expect::Expect;
+    #[cfg(feature = "anyhow")]
+    pub use crate::format::anyhow_report;
+    #[cfg(feature = "base64")]
+    pub use crate::format::base64_encode;
+    pub use crate::format::{error_chain, macro_target, macro_target_owned};
+    pub use crate::heartbeat::Heartbeat;
+    pub use crate::theme::encode_color;
+    pub use log;
+}
+
+#[cfg(feature = "alloc-count")]
+mod alloc;
+mod ansi;
+mod auto_init;
+#[cfg(feature = "binary")]
+mod binary;
+mod builder;
+#[cfg(feature = "test-util")]
+mod capture;
+#[cfg(feature = "config")]
+mod config;
+mod counter;
+mod dedupe;
+mod early;
+#[cfg(all(windows, feature = "windows-eventlog"))]
+mod eventlog;
+mod expect;
+mod file;
+mod format;
+mod group;
+mod heartbeat;
+mod human;
+#[cfg(all(target_os = "linux", feature = "journald"))]
+mod journald;
+mod json;
+mod levels;
+mod logger;
+mod matrix;
+#[cfg(test)]
+mod memory;
+mod meta;
+mod parse;
+mod pipe;
+mod progress;
+mod scope;
+mod shutdown;
+#[cfg(unix)]
+mod signal;
+mod snapshot;
+#[cfg(unix)]
+mod socket;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod theme;
+mod toggle;
+mod verbose;
+mod worker;
+
+#[cfg(feature = "alloc-count")]
+pub use alloc::CountingAllocator;
+pub use ansi::strip_ansi;
+#[cfg(feature = "binary")]
+pub use binary::{decode_binary_log, BinaryRecord};
+pub use builder::{level_from_verbosity, CloggerBuilder, Sink};
+#[cfg(feature = "test-util")]
+pub use capture::TestLogger;
+pub use colored::Color;
+#[cfg(feature = "config")]
+pub use config::init_clogger_from_toml;
+pub use counter::{exit_code_from_logs, exit_if_errors_logged, log_count};
+pub use early::buffer_early_logs;
+#[cfg(unix)]
+pub use file::install_sighup_reopen;
+pub use file::{current_log_path, current_log_size, flush_clogger, set_log_file};
+pub use format::TimestampMode;
+pub use group::GroupGuard;
+pub use human::{human_bytes, human_duration};
+pub use levels::{set_file_level, set_level, set_stdout_level};
+pub use logger::Clogger;
+pub use matrix::{Destination, SinkFormat, SinkMatrix};
+pub use parse::{parse_line, ParsedRecord};
+pub use scope::ScopeGuard;
+pub use shutdown::shutdown;
+#[cfg(unix)]
+pub use signal::async_signal_safe_log;
+pub use snapshot::{current_config, CloggerConfig};
+pub use theme::{ColorTheme, LevelStyle, Style};
+pub use toggle::{disable, enable, is_enabled, is_initialized, set_colors};
+pub use verbose::VerboseGuard;
+
 #[cfg(test)]
 mod tests {
-    use std::process::Command;
+    use std::time::{Duration, Instant};
 
     use super::*;
 
+    /// 性能测试写入的日志条数。
+    const PERF_LINES: usize = 1_000_000;
+    /// 性能测试的耗时上限，远高于正常的耗时，仅用于发现明显的性能退化。
+    const PERF_CEILING: Duration = Duration::from_secs(30);
+
     #[test]
     fn test_logging() {
         init_clogger("/tmp/test_clogger.log");
+        // 重复初始化会被忽略并输出警告
+        init_clogger("/tmp/test_clogger_again.log");
 
         let x = 42;
 
@@ -219,28 +1142,129 @@ mod tests {
         c_error!("这也是一条错误日志！＞﹏＜");
         c_debug!("clogger::tests::test_logging", "这是一条调试输出！(ง •_•)ง");
         c_debug!("这也是一条调试输出！(ง •_•)ง");
+        c_log_at!(
+            log::Level::Warn,
+            "clogger::tests::test_logging",
+            "这是一条动态级别的警告日志！"
+        );
+        c_error_or_warn!(
+            false,
+            "clogger::tests::test_logging",
+            "这是一条重试中的警告日志！"
+        );
+        c_error_or_warn!(true, "这是一条重试耗尽后的错误日志！");
+        {
+            let _group = c_group!("分组");
+            let _outer = c_scope!("clogger::tests::test_logging", "外层作用域");
+            let _inner = c_scope!("内层作用域");
+        }
+        {
+            let _verbose = c_verbose_scope!(log::LevelFilter::Trace);
+            c_debug!(
+                "clogger::tests::test_logging",
+                "这是一条临时提高级别后的调试输出！"
+            );
+        }
+        #[cfg(feature = "base64")]
+        c_base64!("clogger::tests::test_logging", b"CLogger");
+        c_fast!("这是一条快速的常规日志！");
+        c_log_at_time!(
+            chrono::Local::now() - chrono::Duration::hours(1),
+            "clogger::tests::test_logging",
+            "这是一条一小时前的常规日志！"
+        );
+        c_log_colored!(
+            Color::BrightGreen,
+            "clogger::tests::test_logging",
+            "这是一条亮绿色的常规日志！"
+        );
+        for i in 0..2 {
+            c_heartbeat!(Duration::ZERO, || format!("这是第 {} 次心跳！", i));
+        }
         c_debug!(
             "clogger::tests::test_logging()",
             format!("(format! Test) 变量 x 的内容为: {}", x)
         );
     }
 
+    #[test]
+    fn test_dbg() {
+        let x = 21;
+        assert_eq!(c_dbg!(x * 2) + 1, 43);
+        let owned = c_dbg!(String::from("所有权被原样返回"));
+        assert_eq!(owned, "所有权被原样返回");
+    }
+
+    #[test]
+    fn test_sizeof() {
+        let buffer = c_sizeof!("clogger::tests::test_sizeof", [0u64; 128]);
+        assert_eq!(buffer.len(), 128);
+        let name = c_sizeof!(String::from("所有权被原样返回"));
+        assert_eq!(name, "所有权被原样返回");
+    }
+
+    #[test]
+    fn test_enter() {
+        let (id, name) = (7, "chengcheng");
+        c_enter!("clogger::tests::test_enter", id, name);
+        c_enter!(id);
+        // 参数仅被借用，调用后仍然可以使用
+        assert_eq!(name.len(), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "断言失败: `1 + 1 == 3`: 数学出错了")]
+    fn test_assert() {
+        c_debug_assert!(1 + 1 == 2, "clogger::tests::test_assert", "数学没有出错");
+        c_assert!(1 + 1 == 3, "clogger::tests::test_assert", "数学出错了");
+    }
+
+    #[test]
+    #[should_panic(expected = "解析端口: ParseIntError { kind: InvalidDigit }")]
+    fn test_expect() {
+        let port: u16 = c_expect!("8080".parse::<u16>(), "解析端口");
+        assert_eq!(port, 8080);
+        assert_eq!(
+            c_expect!(Some(1), "clogger::tests::test_expect", "缺少值"),
+            1
+        );
+        c_expect!(
+            "八零".parse::<u16>(),
+            "clogger::tests::test_expect",
+            "解析端口"
+        );
+    }
+
+    #[test]
+    fn test_try() {
+        let ok: Result<u32, std::num::ParseIntError> =
+            c_try!("clogger::tests::test_try", "解析数字", "42".parse::<u32>());
+        assert_eq!(ok, Ok(42));
+        let err = c_try!("解析数字", "四十二".parse::<u32>());
+        assert!(err.is_err());
+    }
+
     #[test]
     fn perf_test() {
-        init_clogger("/tmp/test_clogger.log");
+        // 写入 /dev/null 且不输出到终端，只测量格式化与写入管线的开销
+        let logger = CloggerBuilder::new()
+            .log_file("/dev/null")
+            .stdout_level(log::LevelFilter::Off)
+            .build();
 
-        for i in 1..=1_000_000 {
-            c_log!("tests::perf_test()", format!("性能测试 ing... (x{i})"));
+        let start = Instant::now();
+        for i in 1..=PERF_LINES {
+            c_log!(logger => "tests::perf_test()", format!("性能测试 ing... (x{i})"));
         }
+        let elapsed = start.elapsed();
 
-        c_log!(
-            "tests::perf_test()",
-            "开始清空性能测试残留 ('echo > /tmp/test_clogger.log')"
+        println!("{} 条日志耗时 {:?}", PERF_LINES, elapsed);
+        assert!(
+            elapsed < PERF_CEILING,
+            "{} 条日志耗时 {:?}，超出了 {:?} 的上限",
+            PERF_LINES,
+            elapsed,
+            PERF_CEILING
         );
-        Command::new("sh")
-            .arg("-c")
-            .arg("echo > /tmp/test_clogger.log")
-            .output()
-            .expect("清空 /tmp/test_clogger.log 时出现错误");
     }
 }
@@ -0,0 +1,163 @@
+use log::{Level, LevelFilter, Log, Record};
+use std::fmt::Display;
+use std::panic::Location;
+
+/// 独立的 CLogger 实例，通过 [`CloggerBuilder::build`](crate::CloggerBuilder::build) 创建。
+///
+/// 实例拥有自己的输出管线，不会占用全局的 `log` 日志器，因此同一进程中可以同时存在多个实例。
+/// 实例的方法与对应的宏（如 [`c_log!`](crate::c_log)）输出相同格式的日志，也可以通过
+/// `c_log!(logger => "模块名称", "日志内容")` 的形式让宏写入指定的实例。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_warn, CloggerBuilder};
+///
+/// let audit = CloggerBuilder::new()
+///     .log_file("/tmp/clogger_example_audit.log")
+///     .build();
+/// audit.log("example::audit", "用户已登录");
+/// c_warn!(audit => "example::audit", "用户多次输入了错误的密码");
+/// ```
+pub struct Clogger {
+    level: LevelFilter,
+    logger: Box<dyn Log>,
+    show_target: bool,
+}
+
+impl Clogger {
+    pub(crate) fn new(level: LevelFilter, logger: Box<dyn Log>, show_target: bool) -> Self {
+        Clogger {
+            level,
+            logger,
+            show_target,
+        }
+    }
+
+    /// 输出和记录常规日志，日志的级别为 `Info`。
+    ///
+    /// # 参数
+    /// - `module`: 模块名称。
+    /// - `message`: 日志信息内容。
+    #[track_caller]
+    pub fn log(&self, module: &str, message: impl Display) {
+        self.write(Level::Info, module, message, Location::caller());
+    }
+
+    /// 输出和记录警告日志，日志的级别为 `Warn`。
+    ///
+    /// # 参数
+    /// - `module`: 模块名称。
+    /// - `message`: 日志信息内容。
+    #[track_caller]
+    pub fn warn(&self, module: &str, message: impl Display) {
+        self.write(Level::Warn, module, message, Location::caller());
+    }
+
+    /// 输出和记录错误日志，日志的级别为 `Error`。
+    ///
+    /// # 参数
+    /// - `module`: 模块名称。
+    /// - `message`: 日志信息内容。
+    #[track_caller]
+    pub fn error(&self, module: &str, message: impl Display) {
+        self.write(Level::Error, module, message, Location::caller());
+    }
+
+    /// 输出和记录调试日志，日志的级别为 `Debug`。
+    ///
+    /// # 参数
+    /// - `module`: 模块名称。
+    /// - `message`: 日志信息内容。
+    #[track_caller]
+    pub fn debug(&self, module: &str, message: impl Display) {
+        self.write(Level::Debug, module, message, Location::caller());
+    }
+
+    /// 刷新该实例的所有输出端。
+    pub fn flush(&self) {
+        self.logger.flush();
+    }
+
+    /// 构建日志记录并交给输出管线处理。
+    fn write(&self, level: Level, module: &str, message: impl Display, location: &Location) {
+        if level > self.level {
+            return;
+        }
+        // 不显示日志目标时无需构建包含位置信息的目标字符串
+        let target = if self.show_target {
+            format!(
+                "{} ({}:{}^{})",
+                module,
+                location.file(),
+                location.line(),
+                location.column()
+            )
+        } else {
+            module.to_string()
+        };
+        self.logger.log(
+            &Record::builder()
+                .level(level)
+                .target(&target)
+                .file(Some(location.file()))
+                .line(Some(location.line()))
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Clogger, CloggerBuilder};
+
+    #[test]
+    fn test_independent_instances() {
+        let dir = std::env::temp_dir();
+        let path_a = dir.join(format!("clogger_instance_a_{}.log", std::process::id()));
+        let path_b = dir.join(format!("clogger_instance_b_{}.log", std::process::id()));
+
+        let a = CloggerBuilder::new()
+            .log_file(path_a.to_str().unwrap())
+            .build();
+        let b = CloggerBuilder::new()
+            .log_file(path_b.to_str().unwrap())
+            .build();
+        a.log("clogger::tests::a", "写入 A");
+        c_error!(b => "clogger::tests::b", "写入 B");
+
+        let content_a = std::fs::read_to_string(&path_a).unwrap();
+        let content_b = std::fs::read_to_string(&path_b).unwrap();
+        assert!(content_a.contains("[I] [clogger::tests::a (src/logger.rs:"));
+        assert!(content_a.ends_with("写入 A\n"));
+        assert!(content_b.contains("[E] [clogger::tests::b (src/logger.rs:"));
+        assert!(!content_b.contains("写入 A"));
+        std::fs::remove_file(&path_a).unwrap();
+        std::fs::remove_file(&path_b).unwrap();
+    }
+
+    #[test]
+    fn test_track_caller_wrapper() {
+        #[track_caller]
+        fn report(logger: &Clogger, module: &str) {
+            c_warn!(logger => module, "封装后的日志");
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("clogger_track_caller_{}.log", std::process::id()));
+        let logger = CloggerBuilder::new()
+            .log_file(path.to_str().unwrap())
+            .stdout_level(log::LevelFilter::Off)
+            .build();
+        report(&logger, module_path!());
+        let line = line!() - 1;
+
+        // 模块名称与位置信息都来自调用方，而不是封装函数内部
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains(&format!(
+            "[W] [clogger::logger::tests (src/logger.rs:{}^9)]",
+            line
+        )));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
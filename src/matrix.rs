@@ -0,0 +1,288 @@
+use crate::format::{Format, TimestampMode};
+use log::Level;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// 按日志级别配置输出目标的路由表，用于 [`CloggerBuilder::sink_matrix`](crate::CloggerBuilder::sink_matrix)。
+///
+/// 路由表由若干条路由组成，每条路由将一组级别的日志写入一个输出目标，并可以为该目标单独设置日志格式。
+/// 同一级别可以出现在多条路由中，此时该级别的日志会写入所有对应的目标。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{CloggerBuilder, Destination, SinkFormat, SinkMatrix, TimestampMode};
+/// use log::{Level, LevelFilter};
+///
+/// let matrix = SinkMatrix::new()
+///     // 错误与警告输出到标准错误，其余级别输出到标准输出
+///     .route(&[Level::Error, Level::Warn], Destination::stderr())
+///     .route(&[Level::Info, Level::Debug], Destination::stdout())
+///     // 所有级别写入日志文件，并使用便于日志收集系统解析的格式
+///     .route_with_format(
+///         &[Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace],
+///         Destination::file("/tmp/clogger_example_matrix.log"),
+///         SinkFormat::new().timestamp_mode(TimestampMode::Rfc3339).separator(" | "),
+///     );
+///
+/// CloggerBuilder::new()
+///     .level(LevelFilter::Trace)
+///     .stdout_level(LevelFilter::Off) // 终端输出完全由路由表决定
+///     .sink_matrix(matrix)
+///     .init();
+/// ```
+#[derive(Default)]
+pub struct SinkMatrix {
+    pub(crate) routes: Vec<MatrixRoute>,
+}
+
+/// 路由表中的一条路由。
+pub(crate) struct MatrixRoute {
+    /// 各级别（按 `Level as usize - 1` 索引）的日志是否写入该目标。
+    pub(crate) levels: [bool; 5],
+    pub(crate) destination: Destination,
+    pub(crate) format: SinkFormat,
+}
+
+impl SinkMatrix {
+    /// 创建一个空的路由表。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 将 `levels` 中各级别的日志写入 `destination`，使用构建器的日志格式。
+    ///
+    /// # 参数
+    /// - `levels`: 需要写入该目标的日志级别。
+    /// - `destination`: 输出目标。
+    pub fn route(self, levels: &[Level], destination: Destination) -> Self {
+        self.route_with_format(levels, destination, SinkFormat::new())
+    }
+
+    /// 将 `levels` 中各级别的日志以 `format` 指定的格式写入 `destination`。
+    ///
+    /// 同一个文件可以出现在多条路由中（例如以不同的格式写入不同的级别），这些路由会共享同一个文件句柄与缓冲区。
+    ///
+    /// # 参数
+    /// - `levels`: 需要写入该目标的日志级别。
+    /// - `destination`: 输出目标。
+    /// - `format`: 该目标的日志格式。
+    pub fn route_with_format(
+        mut self,
+        levels: &[Level],
+        destination: Destination,
+        format: SinkFormat,
+    ) -> Self {
+        let mut enabled = [false; 5];
+        for level in levels {
+            enabled[*level as usize - 1] = true;
+        }
+        self.routes.push(MatrixRoute {
+            levels: enabled,
+            destination,
+            format,
+        });
+        self
+    }
+
+    /// 判断 `level` 级别的日志是否会被写入任何目标。
+    ///
+    /// # 参数
+    /// - `level`: 日志级别。
+    pub fn is_routed(&self, level: Level) -> bool {
+        self.routes
+            .iter()
+            .any(|route| route.levels[level as usize - 1])
+    }
+}
+
+/// 路由表中的输出目标。
+pub struct Destination {
+    pub(crate) kind: DestinationKind,
+}
+
+/// 输出目标的种类。
+pub(crate) enum DestinationKind {
+    Stdout,
+    Stderr,
+    File(String),
+    Writer(SharedWriter),
+}
+
+impl Destination {
+    /// 标准输出。
+    pub fn stdout() -> Self {
+        Destination {
+            kind: DestinationKind::Stdout,
+        }
+    }
+
+    /// 标准错误。
+    pub fn stderr() -> Self {
+        Destination {
+            kind: DestinationKind::Stderr,
+        }
+    }
+
+    /// 位于 `path` 的日志文件（不带颜色），使用构建器中日志文件的设置（如 [`CloggerBuilder::buffered`](crate::CloggerBuilder::buffered)）打开。
+    ///
+    /// # 参数
+    /// - `path`: 日志文件的保存位置。
+    pub fn file(path: &str) -> Self {
+        Destination {
+            kind: DestinationKind::File(path.to_string()),
+        }
+    }
+
+    /// 自定义的写入端（不带颜色），如网络连接或内存缓冲区。
+    ///
+    /// # 参数
+    /// - `writer`: 写入端。
+    pub fn writer<W: Write + Send + 'static>(writer: W) -> Self {
+        Destination {
+            kind: DestinationKind::Writer(SharedWriter(Arc::new(Mutex::new(Box::new(writer))))),
+        }
+    }
+}
+
+/// 可以被多个输出管线共享的写入端。
+#[derive(Clone)]
+pub(crate) struct SharedWriter(Arc<Mutex<Box<dyn Write + Send>>>);
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).flush()
+    }
+}
+
+/// 路由表中单个输出目标的日志格式，未设置的选项沿用构建器中的设置。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{SinkFormat, TimestampMode};
+///
+/// let format = SinkFormat::new()
+///     .plain(true)
+///     .timestamp_mode(TimestampMode::Utc)
+///     .show_location(false);
+/// ```
+#[derive(Clone, Default)]
+pub struct SinkFormat {
+    plain: Option<bool>,
+    timestamp_mode: Option<TimestampMode>,
+    separator: Option<String>,
+    show_location: Option<bool>,
+    target_width: Option<usize>,
+}
+
+impl SinkFormat {
+    /// 创建一个沿用构建器所有设置的格式。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置是否不为输出内容着色，仅对标准输出与标准错误有效（文件与自定义写入端始终不带颜色）。
+    ///
+    /// # 参数
+    /// - `plain`: 是否不着色。
+    pub fn plain(mut self, plain: bool) -> Self {
+        self.plain = Some(plain);
+        self
+    }
+
+    /// 设置时间戳的格式，见 [`CloggerBuilder::timestamp_mode`](crate::CloggerBuilder::timestamp_mode)。
+    ///
+    /// # 参数
+    /// - `mode`: 时间戳的格式。
+    pub fn timestamp_mode(mut self, mode: TimestampMode) -> Self {
+        self.timestamp_mode = Some(mode);
+        self
+    }
+
+    /// 设置各字段之间的分隔符，见 [`CloggerBuilder::separator`](crate::CloggerBuilder::separator)。
+    ///
+    /// # 参数
+    /// - `separator`: 字段之间的分隔符。
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = Some(separator.to_string());
+        self
+    }
+
+    /// 设置是否显示调用位置，见 [`CloggerBuilder::show_location`](crate::CloggerBuilder::show_location)。
+    ///
+    /// # 参数
+    /// - `show`: 是否显示调用位置。
+    pub fn show_location(mut self, show: bool) -> Self {
+        self.show_location = Some(show);
+        self
+    }
+
+    /// 设置模块名称及位置信息所占的列宽，见 [`CloggerBuilder::target_width`](crate::CloggerBuilder::target_width)。
+    ///
+    /// # 参数
+    /// - `width`: 列宽。
+    pub fn target_width(mut self, width: usize) -> Self {
+        self.target_width = Some(width);
+        self
+    }
+
+    /// 判断输出到终端的日志是否着色，`color` 为构建器对该流的判断结果。
+    pub(crate) fn color(&self, color: bool) -> bool {
+        color && self.plain != Some(true)
+    }
+
+    /// 在构建器的日志格式上应用该格式中设置的选项。
+    pub(crate) fn apply(&self, mut format: Format) -> Format {
+        if let Some(mode) = &self.timestamp_mode {
+            format.timestamp_mode = mode.clone();
+        }
+        if let Some(separator) = &self.separator {
+            format.separator = Some(separator.clone());
+        }
+        if let Some(show) = self.show_location {
+            format.hide_location = !show;
+        }
+        if let Some(width) = self.target_width {
+            format.target_width = Some(width);
+        }
+        format
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sink_matrix() {
+        let matrix = SinkMatrix::new()
+            .route(&[Level::Error, Level::Warn], Destination::stderr())
+            .route(&[Level::Warn, Level::Info], Destination::stdout());
+        let levels: Vec<[bool; 5]> = matrix.routes.iter().map(|route| route.levels).collect();
+        assert_eq!(
+            levels,
+            [
+                [true, true, false, false, false],
+                [false, true, true, false, false]
+            ]
+        );
+        assert!(matrix.is_routed(Level::Warn));
+        assert!(!matrix.is_routed(Level::Trace));
+
+        let format = SinkFormat::new()
+            .plain(true)
+            .timestamp_mode(TimestampMode::Utc)
+            .show_location(false)
+            .apply(Format {
+                separator: Some("|".to_string()),
+                ..Default::default()
+            });
+        assert_eq!(format.timestamp_mode, TimestampMode::Utc);
+        assert_eq!(format.separator.as_deref(), Some("|"));
+        assert!(format.hide_location);
+        assert!(!SinkFormat::new().plain(true).color(true));
+    }
+}
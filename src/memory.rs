@@ -0,0 +1,27 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// 将日志写入内存缓冲区的写入端，克隆得到的写入端共享同一缓冲区。
+#[derive(Clone, Default)]
+pub(crate) struct MemoryWriter(Arc<Mutex<Vec<u8>>>);
+
+impl MemoryWriter {
+    /// 获取目前写入的全部内容。
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap_or_else(|e| e.into_inner())).into_owned()
+    }
+}
+
+impl Write for MemoryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use crate::file::FileOutput;
+use crate::format::{Format, TimestampMode};
+use crate::json::write_str;
+use crate::levels::global_levels;
+use chrono::{Local, SecondsFormat};
+use std::fmt::Write as _;
+use std::sync::{Arc, OnceLock};
+
+/// 元数据行中标记其类型的键，日志文件中以 `{"_clogger_meta":` 开头的行均为元数据行。
+const META_KEY: &str = "_clogger_meta";
+
+/// 全局 CLogger 日志文件的格式描述（JSON 对象），在初始化时记录。
+static FILE_SCHEMA: OnceLock<String> = OnceLock::new();
+
+/// 全局 CLogger 日志文件的写入端，元数据行与日志经过同一个写入端，保持写入的顺序。
+static FILE_OUTPUT: OnceLock<Arc<FileOutput>> = OnceLock::new();
+
+/// 将日志文件使用的格式转换为 JSON 对象，写入元数据行的 `schema` 字段。
+pub(crate) fn schema_object(format: &Format) -> String {
+    let mut object = String::from("{\"format\":\"text\",\"timestamp\":");
+    let timestamp = match &format.timestamp_mode {
+        TimestampMode::Local => "local".to_string(),
+        TimestampMode::Utc => "utc".to_string(),
+        TimestampMode::Rfc3339 => "rfc3339".to_string(),
+        TimestampMode::Custom(custom) => format!("custom:{}", custom),
+    };
+    write_str(&mut object, &timestamp);
+    object.push_str(",\"separator\":");
+    match &format.separator {
+        Some(separator) => write_str(&mut object, separator),
+        None => object.push_str("null"),
+    }
+    object.push_str(",\"app_tag\":");
+    match &format.app_tag {
+        Some(app_tag) => write_str(&mut object, app_tag),
+        None => object.push_str("null"),
+    }
+    let _ = write!(
+        object,
+        ",\"show_target\":{},\"show_location\":{},\"show_crate_version\":{}}}",
+        !format.hide_target, !format.hide_location, format.show_crate_version
+    );
+    object
+}
+
+/// 记录全局 CLogger 日志文件的格式描述。
+pub(crate) fn set_file_schema(schema: String) {
+    let _ = FILE_SCHEMA.set(schema);
+}
+
+/// 记录全局 CLogger 日志文件的写入端。
+pub(crate) fn set_file_output(output: Arc<FileOutput>) {
+    let _ = FILE_OUTPUT.set(output);
+}
+
+/// 生成一行说明日志文件的内容在运行时发生变化的元数据行（不包含换行符）。
+///
+/// `change` 为发生变化的配置项，如 `level`、`file_level` 与 `log_file`。
+fn format_change_line(change: &str, schema: &str) -> String {
+    let mut line = format!("{{\"{}\":\"format_change\",\"change\":", META_KEY);
+    write_str(&mut line, change);
+    line.push_str(",\"time\":");
+    write_str(
+        &mut line,
+        &Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+    );
+    let _ = write!(line, ",\"schema\":{}", schema);
+    if let Some(levels) = global_levels() {
+        let _ = write!(
+            line,
+            ",\"level\":\"{}\",\"file_level\":\"{}\"",
+            levels.global(),
+            levels.file()
+        );
+    }
+    line.push('}');
+    line
+}
+
+/// 在全局 CLogger 的日志文件中写入一行元数据，说明 `change` 在运行时发生了变化。
+///
+/// 自动处理日志的程序可以据此发现之后的日志在格式或内容上的变化。没有配置日志文件时不做任何事。
+/// 元数据行与日志经过同一个写入端，启用非阻塞写入时也会排在之前输出的日志之后。
+pub(crate) fn write_format_change(change: &str) {
+    let (Some(output), Some(schema)) = (FILE_OUTPUT.get(), FILE_SCHEMA.get()) else {
+        return;
+    };
+    output.write_line(format!("{}\n", format_change_line(change, schema)), false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_change_line() {
+        let schema = schema_object(&Format {
+            timestamp_mode: TimestampMode::Custom("%H:%M".to_string()),
+            separator: Some("|".to_string()),
+            hide_location: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            schema,
+            r#"{"format":"text","timestamp":"custom:%H:%M","separator":"|","app_tag":null,"show_target":true,"show_location":false,"show_crate_version":false}"#
+        );
+        let line = format_change_line("file_level", &schema);
+        assert!(
+            line.starts_with(r#"{"_clogger_meta":"format_change","change":"file_level","time":""#)
+        );
+        assert!(line.contains(&format!(",\"schema\":{}", schema)));
+        assert!(line.ends_with('}'));
+        // 元数据行不会被当作普通的日志解析
+        assert!(crate::parse_line(&line).is_none());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::format::{parse_location, split_target};
+use log::Level;
+
+/// 由 [`parse_line`] 解析得到的一条日志。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedRecord {
+    /// 时间戳的原始文本。
+    pub timestamp: String,
+    /// 附加在日志中的标签（见 [`CloggerBuilder::app_tag`](crate::CloggerBuilder::app_tag)）。
+    pub app_tag: Option<String>,
+    /// 日志级别。
+    pub level: Level,
+    /// 模块名称。
+    pub target: String,
+    /// 调用位置所在的文件。
+    pub file: Option<String>,
+    /// 调用位置所在的行号。
+    pub line: Option<u32>,
+    /// 调用位置所在的列号（`c_log!` 与直接使用 `log` 宏的日志不包含列号）。
+    pub column: Option<u32>,
+    /// 日志信息内容。
+    pub message: String,
+}
+
+/// 将 CLogger 默认格式（不带颜色，如日志文件中）的一行日志解析为 [`ParsedRecord`]。
+///
+/// 支持默认的级别标记（`I`、`W`、`E`、`D`、`T`）、可选的标签以及 [`CloggerBuilder::target_width`](crate::CloggerBuilder::target_width)
+/// 补齐的空格。日志内容中的括号不会影响解析。模块名称被截断时将无法得到位置信息。
+/// 行的格式不符合时返回 `None`，运行时调整配置后写入的 JSON 元数据行（以 `{"_clogger_meta":` 开头）同样返回 `None`。
+///
+/// # 示例
+/// ```rust
+/// use clogger::parse_line;
+/// use log::Level;
+///
+/// let record =
+///     parse_line("(2024-06-01 12:00:00.000) [W] [app::net (src/net.rs:42^7)] 连接 [db] 超时").unwrap();
+/// assert_eq!(record.level, Level::Warn);
+/// assert_eq!(record.target, "app::net");
+/// assert_eq!(record.file.as_deref(), Some("src/net.rs"));
+/// assert_eq!((record.line, record.column), (Some(42), Some(7)));
+/// assert_eq!(record.message, "连接 [db] 超时");
+/// ```
+///
+/// # 参数
+/// - `line`: 一行日志（可以包含末尾的换行符，以及文件开头的 UTF-8 BOM）。
+pub fn parse_line(line: &str) -> Option<ParsedRecord> {
+    let line = line.strip_prefix('\u{feff}').unwrap_or(line);
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    let (timestamp, rest) = line.strip_prefix('(')?.split_once(") ")?;
+    let (first, rest) = bracketed(rest)?;
+    let (app_tag, level, rest) = match marker_level(first) {
+        Some(level) => (None, level, rest),
+        None => {
+            let (marker, rest) = bracketed(rest)?;
+            (Some(first.to_string()), marker_level(marker)?, rest)
+        }
+    };
+    let (target, message) = bracketed(rest)?;
+    let (module, location) = split_target(target);
+    let (file, line, column) = match location.and_then(parse_location) {
+        Some((file, line, column)) => (Some(file.to_string()), Some(line), column),
+        None => (None, None, None),
+    };
+    Some(ParsedRecord {
+        timestamp: timestamp.to_string(),
+        app_tag,
+        level,
+        target: module.to_string(),
+        file,
+        line,
+        column,
+        message: message.to_string(),
+    })
+}
+
+/// 解析以 `[内容]` 开头的文本，返回括号中的内容与其后（去掉对齐用的空格）的文本。
+///
+/// 括号后必须是空格或文本的结尾，因此括号内容中的 `]` 不会导致提前结束。
+fn bracketed(text: &str) -> Option<(&str, &str)> {
+    let text = text.strip_prefix('[')?;
+    let mut search = 0;
+    loop {
+        let end = search + text[search..].find(']')?;
+        let rest = &text[end + 1..];
+        if rest.is_empty() || rest.starts_with(' ') {
+            // 级别标记与模块名称之后可能有补齐列宽的空格，日志内容之前只会有一个分隔空格
+            let padding = rest.len() - rest.trim_start_matches(' ').len();
+            let rest = if rest.len() > padding {
+                &rest[padding..]
+            } else {
+                ""
+            };
+            return Some((&text[..end], rest));
+        }
+        search = end + 1;
+    }
+}
+
+/// 将默认的级别标记转换为日志级别。
+fn marker_level(marker: &str) -> Option<Level> {
+    match marker.trim_end() {
+        "E" => Some(Level::Error),
+        "W" => Some(Level::Warn),
+        "I" => Some(Level::Info),
+        "D" => Some(Level::Debug),
+        "T" => Some(Level::Trace),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    #[test]
+    fn test_parse_rendered_line() {
+        let format = Format {
+            app_tag: Some("v1.4.2".to_string()),
+            target_width: Some(40),
+            ..Default::default()
+        };
+        let line = format.render(
+            &format_args!("[缓存] 命中率 ] 为 (95%)"),
+            &log::Record::builder()
+                .level(log::Level::Debug)
+                .target(r"app::cache (C:\src\cache.rs:42^7)")
+                .build(),
+        );
+        let record = parse_line(&line).unwrap();
+        assert_eq!(record.app_tag.as_deref(), Some("v1.4.2"));
+        assert_eq!(record.level, Level::Debug);
+        assert_eq!(record.target, "app::cache");
+        assert_eq!(record.file.as_deref(), Some(r"C:\src\cache.rs"));
+        assert_eq!((record.line, record.column), (Some(42), Some(7)));
+        assert_eq!(record.message, "[缓存] 命中率 ] 为 (95%)");
+    }
+
+    #[test]
+    fn test_parse_invalid_line() {
+        assert_eq!(parse_line("不是 CLogger 的日志"), None);
+        assert_eq!(parse_line("(2024-06-01 12:00:00.000) [?] [app] 内容"), None);
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::{self, Write};
+
+/// 在管道被关闭后停止写入的写入端。
+///
+/// 将程序的输出通过管道传给 `head` 等工具时，对方退出后再写入会得到 `BrokenPipe` 错误。
+/// 该写入端在遇到 `BrokenPipe` 后会丢弃之后的所有内容，而不是将错误交给 fern 处理，
+/// 其他输出端（如日志文件）不受影响。
+pub(crate) struct BrokenPipeGuard<W> {
+    writer: W,
+    closed: bool,
+}
+
+impl<W: Write> BrokenPipeGuard<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        BrokenPipeGuard {
+            writer,
+            closed: false,
+        }
+    }
+
+    /// 处理写入结果，遇到 `BrokenPipe` 时标记管道已关闭并忽略该错误。
+    fn check<T>(&mut self, result: io::Result<T>, default: T) -> io::Result<T> {
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(default)
+            }
+            result => result,
+        }
+    }
+}
+
+impl<W: Write> Write for BrokenPipeGuard<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.closed {
+            return Ok(buf.len());
+        }
+        let result = self.writer.write(buf);
+        self.check(result, buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        let result = self.writer.flush();
+        self.check(result, ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 写入指定次数后模拟管道被关闭的写入端。
+    struct ClosingPipe {
+        remaining: usize,
+        written: Vec<u8>,
+    }
+
+    impl Write for ClosingPipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.remaining == 0 {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            self.remaining -= 1;
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_broken_pipe_is_ignored() {
+        let mut guard = BrokenPipeGuard::new(ClosingPipe {
+            remaining: 1,
+            written: Vec::new(),
+        });
+        guard.write_all(b"first\n").unwrap();
+        guard.write_all(b"second\n").unwrap();
+        guard.write_all(b"third\n").unwrap();
+        guard.flush().unwrap();
+        assert!(guard.closed);
+        assert_eq!(guard.writer.written, b"first\n");
+    }
+}
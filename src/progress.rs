@@ -0,0 +1,78 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// 清除终端当前行的控制序列：回到行首并清除到行尾。
+const CLEAR_LINE: &[u8] = b"\r\x1b[K";
+
+/// 重新绘制进度条的回调函数，用于 [`CloggerBuilder::progress_redraw`](crate::CloggerBuilder::progress_redraw)。
+pub(crate) type Redraw = Arc<dyn Fn() + Send + Sync>;
+
+/// 与 `\r` 进度条协调输出的写入端。
+///
+/// 每条日志写入前先清除终端的当前行（即进度条），日志写入并刷新后调用回调函数重新绘制进度条，
+/// 使日志始终显示在进度条的上方，而不会与进度条混在同一行中。
+pub(crate) struct ProgressWriter<W> {
+    writer: W,
+    redraw: Redraw,
+    line_start: bool,
+}
+
+impl<W: Write> ProgressWriter<W> {
+    pub(crate) fn new(writer: W, redraw: Redraw) -> Self {
+        ProgressWriter {
+            writer,
+            redraw,
+            line_start: true,
+        }
+    }
+}
+
+impl<W: Write> Write for ProgressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.line_start {
+            self.writer.write_all(CLEAR_LINE)?;
+            self.line_start = false;
+        }
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        // fern 在每条日志写入后刷新，此时日志已经完整输出
+        if !self.line_start {
+            self.line_start = true;
+            (self.redraw)();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_progress_writer() {
+        let redraws = Arc::new(AtomicUsize::new(0));
+        let counter = redraws.clone();
+        let mut writer = ProgressWriter::new(
+            Vec::new(),
+            Arc::new(move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }),
+        );
+        for line in ["第一条日志", "第二条日志"] {
+            writeln!(writer, "{}", line).unwrap();
+            writer.flush().unwrap();
+        }
+        // 没有新的日志时刷新不会重新绘制
+        writer.flush().unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.writer).unwrap(),
+            "\r\x1b[K第一条日志\n\r\x1b[K第二条日志\n"
+        );
+        assert_eq!(redraws.load(Ordering::Relaxed), 2);
+    }
+}
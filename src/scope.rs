@@ -0,0 +1,56 @@
+use crate::human::human_duration;
+use std::cell::Cell;
+use std::time::Instant;
+
+thread_local! {
+    /// 当前线程中嵌套的作用域层数。
+    static SCOPE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// 记录作用域进入与离开的 RAII 守卫，通常通过 [`c_scope!`](crate::c_scope) 创建。
+///
+/// 创建时会以 `Debug` 级别输出 `> 标签`，离开作用域时输出 `< 标签 (耗时)`，
+/// 耗时以 [`human_duration`](crate::human_duration) 的格式输出。
+/// 嵌套的作用域会根据所在线程的嵌套层数进行缩进。
+pub struct ScopeGuard {
+    target: String,
+    label: String,
+    depth: usize,
+    start: Instant,
+}
+
+impl ScopeGuard {
+    /// 进入作用域并输出进入日志。
+    ///
+    /// # 参数
+    /// - `target`: 日志的目标（包含位置信息）。
+    /// - `label`: 作用域的标签。
+    #[doc(hidden)]
+    pub fn new(target: String, label: String) -> Self {
+        let depth = SCOPE_DEPTH.with(|d| {
+            let depth = d.get();
+            d.set(depth + 1);
+            depth
+        });
+        log::debug!(target: &target, "{}> {}", "  ".repeat(depth), label);
+        ScopeGuard {
+            target,
+            label,
+            depth,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        log::debug!(
+            target: &self.target,
+            "{}< {} ({})",
+            "  ".repeat(self.depth),
+            self.label,
+            human_duration(self.start.elapsed())
+        );
+        SCOPE_DEPTH.with(|d| d.set(self.depth));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::counter::print_shutdown_summary;
+use crate::file::flush_clogger;
+use crate::toggle::mark_shut_down;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// [`shutdown`] 等待后台线程退出的最长时间。
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 后台线程在空闲时检查停止信号的间隔。
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 所有仍在运行的后台线程。
+static WORKERS: Mutex<Vec<Worker>> = Mutex::new(Vec::new());
+
+/// 通知后台线程停止的信号。
+#[derive(Clone, Default)]
+pub(crate) struct StopSignal(Arc<(Mutex<bool>, Condvar)>);
+
+impl StopSignal {
+    /// 发出停止信号，唤醒正在等待的线程。
+    fn stop(&self) {
+        let (stopped, condvar) = &*self.0;
+        *stopped.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        condvar.notify_all();
+    }
+
+    /// 是否已发出停止信号。
+    pub(crate) fn is_stopped(&self) -> bool {
+        *self.0 .0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 最多等待 `timeout`，在此期间收到停止信号时立即返回 `true`。
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        let (stopped, condvar) = &*self.0;
+        let stopped = stopped.lock().unwrap_or_else(|e| e.into_inner());
+        *condvar
+            .wait_timeout_while(stopped, timeout, |stopped| !*stopped)
+            .unwrap_or_else(|e| e.into_inner())
+            .0
+    }
+}
+
+/// 由 CLogger 启动的后台线程。
+struct Worker {
+    stop: StopSignal,
+    thread: JoinHandle<()>,
+}
+
+/// 启动一个 CLogger 的后台线程，[`shutdown`] 时会通知其停止并等待其退出。
+///
+/// 线程需要定期检查传入的 [`StopSignal`]，收到停止信号后写入所有积压的内容再退出。
+pub(crate) fn spawn_worker<F>(name: &str, f: F)
+where
+    F: FnOnce(StopSignal) + Send + 'static,
+{
+    let stop = StopSignal::default();
+    let signal = stop.clone();
+    let thread = std::thread::Builder::new()
+        .name(format!("clogger-{}", name))
+        .spawn(move || f(signal))
+        .unwrap_or_else(|e| panic!("无法启动 CLogger 的后台线程 {}: {}", name, e));
+    let mut workers = WORKERS.lock().unwrap_or_else(|e| e.into_inner());
+    // 顺便清理已经退出的线程（如被释放的 Clogger 实例的写入线程）
+    workers.retain(|worker| !worker.thread.is_finished());
+    workers.push(Worker { stop, thread });
+}
+
+/// 通知所有后台线程停止，并在 `timeout` 内等待其退出，全部退出时返回 `true`。
+fn stop_and_join(workers: Vec<Worker>, timeout: Duration) -> bool {
+    for worker in &workers {
+        worker.stop.stop();
+    }
+    let deadline = Instant::now() + timeout;
+    let mut all_finished = true;
+    for worker in workers {
+        while !worker.thread.is_finished() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        if worker.thread.is_finished() {
+            let _ = worker.thread.join();
+        } else {
+            all_finished = false;
+        }
+    }
+    all_finished
+}
+
+/// 关闭 CLogger，确保所有日志都已写入。
+///
+/// 该函数会停止接收新的日志，通知所有后台线程（如非阻塞写入、定期刷新与 SQLite 写入线程）写入积压的日志后退出，
+/// 最多等待 5 秒，最后刷新所有输出端。适合在很快就会结束的程序（如命令行工具）中于 `main` 返回前调用。
+///
+/// 调用该函数后，之后的所有日志都会被直接丢弃，且无法通过 [`enable`](crate::enable) 恢复。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_log, CloggerBuilder};
+///
+/// CloggerBuilder::new()
+///     .log_file("/tmp/clogger_example.log")
+///     .non_blocking(true)
+///     .init();
+/// c_log!("example::moudle_name", "这是一条常规日志！(づ｡◕‿‿◕｡)づ");
+/// clogger::shutdown(); // 确保上面的日志已写入后再退出
+/// ```
+pub fn shutdown() {
+    // 在停止接收日志之前刷新，使合并的重复次数等暂存的内容仍能交给后台线程写入
+    log::logger().flush();
+    mark_shut_down();
+    let workers = std::mem::take(&mut *WORKERS.lock().unwrap_or_else(|e| e.into_inner()));
+    if !stop_and_join(workers, SHUTDOWN_TIMEOUT) {
+        eprintln!(
+            "CLogger 关闭时部分后台线程未能在 {:?} 内退出",
+            SHUTDOWN_TIMEOUT
+        );
+    }
+    flush_clogger();
+    print_shutdown_summary();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_stop_and_join() {
+        let (sender, receiver) = mpsc::channel();
+        let stop = StopSignal::default();
+        let signal = stop.clone();
+        let thread = std::thread::spawn(move || {
+            // 模拟定期刷新线程：等待停止信号，退出前完成最后一次写入
+            while !signal.wait(Duration::from_secs(60)) {}
+            sender.send("已写入积压的日志").unwrap();
+        });
+
+        let start = Instant::now();
+        assert!(stop_and_join(
+            vec![Worker { stop, thread }],
+            Duration::from_secs(5)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(receiver.try_recv().unwrap(), "已写入积压的日志");
+    }
+}
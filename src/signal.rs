@@ -0,0 +1,80 @@
+use crate::file::ACTIVE_FILE;
+use log::Level;
+use std::fs::File;
+use std::io::Write;
+use std::mem::ManuallyDrop;
+use std::os::unix::io::{FromRawFd, RawFd};
+
+/// 标准错误的文件描述符。
+const STDERR_FD: RawFd = 2;
+
+/// 在信号处理函数中输出并记录一条日志（仅 Unix 平台可用）。
+///
+/// 常规的日志路径会分配内存并获取锁，在信号处理函数中调用可能导致死锁或未定义行为。
+/// 该函数只使用异步信号安全的操作：不分配内存、不获取锁，直接通过 `write` 系统调用将
+/// `(signal) [级别] 日志内容` 形式的一行写入标准错误，以及 CLogger 当前正在写入的日志文件（若已配置）。
+/// 因此日志中没有时间戳与模块名称，也不会经过级别过滤与其他输出端，日志内容只能是 `&'static str`。
+///
+/// 该函数不会刷新日志文件的缓冲区，启用 [`CloggerBuilder::buffered`](crate::CloggerBuilder::buffered) 时，
+/// 缓冲区中尚未写入的日志可能出现在该行之后，甚至随进程一起丢失。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{async_signal_safe_log, init_clogger};
+/// use log::Level;
+///
+/// extern "C" fn on_sigsegv(_: i32) {
+///     async_signal_safe_log(Level::Error, "收到 SIGSEGV，进程即将退出");
+/// }
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// // 通过 `libc::signal` 或 `signal_hook::low_level::register` 等方式注册 `on_sigsegv`
+/// # on_sigsegv(11);
+/// ```
+///
+/// # 参数
+/// - `level`: 日志级别。
+/// - `message`: 日志信息内容。
+pub fn async_signal_safe_log(level: Level, message: &'static str) {
+    write_line(STDERR_FD, level, message);
+    if let Some(file) = ACTIVE_FILE.get() {
+        write_line(file.raw_fd(), level, message);
+    }
+}
+
+/// 将一行日志直接写入文件描述符 `fd`，写入失败时忽略错误。
+fn write_line(fd: RawFd, level: Level, message: &str) {
+    let marker: &[u8] = match level {
+        Level::Error => b"(signal) [E] ",
+        Level::Warn => b"(signal) [W] ",
+        Level::Info => b"(signal) [I] ",
+        Level::Debug => b"(signal) [D] ",
+        Level::Trace => b"(signal) [T] ",
+    };
+    // 借用文件描述符，`ManuallyDrop` 保证不会关闭它；`File` 的写入直接调用 `write` 系统调用，不会分配内存或加锁
+    let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    let _ = file.write_all(marker);
+    let _ = file.write_all(message.as_bytes());
+    let _ = file.write_all(b"\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::io::AsRawFd;
+
+    #[test]
+    fn test_write_line() {
+        let path = std::env::temp_dir().join(format!("clogger_signal_{}.log", std::process::id()));
+        let file = File::create(&path).unwrap();
+        write_line(file.as_raw_fd(), Level::Error, "收到 SIGSEGV");
+        write_line(file.as_raw_fd(), Level::Warn, "正在退出");
+        drop(file);
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "(signal) [E] 收到 SIGSEGV\n(signal) [W] 正在退出\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
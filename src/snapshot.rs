@@ -0,0 +1,83 @@
+use crate::file::current_log_path;
+use crate::format::TimestampMode;
+use crate::levels::global_levels;
+use crate::toggle::colors_override;
+use log::{Level, LevelFilter};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// 全局 CLogger 初始化时生效的配置。
+static CURRENT: OnceLock<CloggerConfig> = OnceLock::new();
+
+/// 全局 CLogger 实际生效的配置的快照，通过 [`current_config`] 获取。
+///
+/// 快照记录的是综合了构建器、配置文件与环境变量（如 `CLOGGER_LEVEL`、`NO_COLOR`）之后的最终结果，
+/// 适合在启动时输出，用于排查日志没有按预期输出的原因。
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CloggerConfig {
+    /// 全局日志级别。
+    pub level: LevelFilter,
+    /// 全局日志级别是否来自 `CLOGGER_LEVEL` 或 `RUST_LOG` 环境变量。
+    pub level_from_env: bool,
+    /// 标准输出的最低日志级别。
+    pub stdout_level: LevelFilter,
+    /// 额外输出到标准错误的最低日志级别。
+    pub stderr_level: Option<LevelFilter>,
+    /// 写入日志文件的最低日志级别。
+    pub file_level: LevelFilter,
+    /// 当前正在写入的日志文件。
+    pub log_file: Option<PathBuf>,
+    /// 按模块名称前缀路由的日志文件，元素为 `(前缀, 日志文件)`。
+    pub target_files: Vec<(String, String)>,
+    /// 按日志级别写入的日志文件，元素为 `(级别, 日志文件)`。
+    pub level_files: Vec<(Level, String)>,
+    /// 已配置的输出端的名称，如 `stdout`、`stderr`、`file`、`sqlite`。
+    pub sinks: Vec<String>,
+    /// 输出到标准输出的日志是否着色。
+    pub stdout_color: bool,
+    /// 输出到标准错误的日志是否着色。
+    pub stderr_color: bool,
+    /// 时间戳的格式。
+    pub timestamp_mode: TimestampMode,
+    /// 通过 [`CloggerBuilder::filter`](crate::CloggerBuilder::filter) 等方法添加的过滤函数的数量。
+    pub filter_count: usize,
+}
+
+/// 记录全局 CLogger 初始化时生效的配置。
+pub(crate) fn set_current_config(config: CloggerConfig) {
+    let _ = CURRENT.set(config);
+}
+
+/// 获取全局 CLogger 实际生效的配置。
+///
+/// 若 CLogger 尚未通过 [`CloggerBuilder::init`](crate::CloggerBuilder::init) 等方式初始化，将返回 `None`。
+/// 通过 [`CloggerBuilder::build`](crate::CloggerBuilder::build) 创建的独立实例不会影响该配置。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_debug, current_config, init_clogger};
+/// use log::{Level, LevelFilter};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// let config = current_config().unwrap();
+/// c_debug!("example::moudle_name", format!("生效的配置: {:?}", config));
+/// if config.level < LevelFilter::Debug {
+///     eprintln!("调试日志未启用，请检查 CLOGGER_LEVEL 环境变量");
+/// }
+/// ```
+pub fn current_config() -> Option<CloggerConfig> {
+    let mut config = CURRENT.get()?.clone();
+    config.log_file = current_log_path();
+    // 日志级别、日志文件与终端的着色可以在运行时调整
+    if let Some(levels) = global_levels() {
+        config.level = levels.global();
+        config.stdout_level = levels.stdout();
+        config.file_level = levels.file();
+    }
+    if let Some(enabled) = colors_override() {
+        config.stdout_color = enabled;
+        config.stderr_color = enabled;
+    }
+    Some(config)
+}
@@ -0,0 +1,128 @@
+use std::io::{self, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// 重连等待时间的初始值。
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+/// 重连等待时间的上限。
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// 单次发送的超时时间，超时后视为连接断开，避免不再读取的收集器阻塞所有写入日志的线程。
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 将日志写入 Unix 域套接字的输出端。
+///
+/// 每条日志会先写入缓冲区，在 fern 调用 `flush` 时整行发送，避免重连时发送不完整的行。
+/// 连接断开后会按指数退避的方式重连，等待重连期间的日志将被丢弃。
+/// 收集器停止读取导致发送超过 [`WRITE_TIMEOUT`] 时同样视为连接断开。
+pub(crate) struct UnixSocketSink {
+    path: PathBuf,
+    stream: Option<UnixStream>,
+    pending: Vec<u8>,
+    backoff: Duration,
+    next_retry: Instant,
+}
+
+impl UnixSocketSink {
+    /// 创建输出端并尝试连接到 `path`，连接失败时将在之后写入日志时重试。
+    pub(crate) fn new(path: &str) -> Self {
+        let mut sink = UnixSocketSink {
+            path: PathBuf::from(path),
+            stream: None,
+            pending: Vec::new(),
+            backoff: INITIAL_BACKOFF,
+            next_retry: Instant::now(),
+        };
+        sink.connect();
+        sink
+    }
+
+    /// 尝试建立连接，失败时推迟下一次重试的时间。
+    fn connect(&mut self) {
+        match UnixStream::connect(&self.path).and_then(|stream| {
+            stream
+                .set_write_timeout(Some(WRITE_TIMEOUT))
+                .map(|_| stream)
+        }) {
+            Ok(stream) => {
+                self.stream = Some(stream);
+                self.backoff = INITIAL_BACKOFF;
+            }
+            Err(_) => self.disconnect(),
+        }
+    }
+
+    /// 断开连接，并将等待时间翻倍（不超过上限）。
+    fn disconnect(&mut self) {
+        self.stream = None;
+        self.next_retry = Instant::now() + self.backoff;
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+impl Write for UnixSocketSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.stream.is_none() && Instant::now() >= self.next_retry {
+            self.connect();
+        }
+        if let Some(stream) = &mut self.stream {
+            if stream.write_all(&self.pending).is_err() {
+                self.disconnect();
+            }
+        }
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixListener;
+
+    #[test]
+    fn test_unix_socket_reconnect() {
+        let path = std::env::temp_dir().join(format!("clogger_test_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // 收集器尚未启动时写入的日志会被丢弃
+        let mut sink = UnixSocketSink::new(path.to_str().unwrap());
+        writeln!(sink, "丢弃的日志").unwrap();
+        sink.flush().unwrap();
+
+        let listener = UnixListener::bind(&path).unwrap();
+        std::thread::sleep(INITIAL_BACKOFF);
+        writeln!(sink, "这是一条常规日志！").unwrap();
+        sink.flush().unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        assert_eq!(line, "这是一条常规日志！\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_unix_socket_write_timeout() {
+        let path =
+            std::env::temp_dir().join(format!("clogger_stalled_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let mut sink = UnixSocketSink::new(path.to_str().unwrap());
+        // 收集器接受连接后不再读取
+        let (_stream, _) = listener.accept().unwrap();
+
+        let start = Instant::now();
+        sink.write_all(&vec![b'a'; 16 * 1024 * 1024]).unwrap();
+        sink.flush().unwrap();
+        assert!(start.elapsed() < WRITE_TIMEOUT * 5);
+        assert!(sink.stream.is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
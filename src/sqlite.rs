@@ -0,0 +1,243 @@
+use crate::format::{capped_message, parse_location, record_time, split_target, Clock, FieldMask};
+use crate::json;
+use crate::shutdown::{spawn_worker, StopSignal, POLL_INTERVAL};
+use crate::worker::FLUSH_TIMEOUT;
+use chrono::{Local, SecondsFormat};
+use rusqlite::{params, Connection};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+
+/// 单个事务中最多写入的日志条数。
+const BATCH_SIZE: usize = 1000;
+
+/// `logs` 表的结构。
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS logs (
+    id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    ts      TEXT NOT NULL,
+    level   TEXT NOT NULL,
+    target  TEXT NOT NULL,
+    file    TEXT,
+    line    INTEGER,
+    message TEXT NOT NULL,
+    fields  TEXT NOT NULL
+)";
+
+/// 待写入数据库的一行日志。
+struct Row {
+    ts: String,
+    level: &'static str,
+    target: String,
+    file: Option<String>,
+    line: Option<u32>,
+    message: String,
+    fields: String,
+}
+
+/// 发送给写入线程的消息。
+enum Message {
+    /// 一行待写入的日志。
+    Row(Row),
+    /// 提交之前的所有日志后通过该发送端通知。
+    Flush(Sender<()>),
+}
+
+/// 将日志写入 SQLite 数据库的输出端。
+///
+/// 日志会被发送给后台线程，由后台线程将积压的日志合并到同一个事务中批量写入。
+/// 刷新时会等待后台线程提交所有积压的日志，最多等待 [`FLUSH_TIMEOUT`]。
+pub(crate) struct SqliteSink {
+    sender: Sender<Message>,
+    clock: Option<Clock>,
+    global_fields: Arc<[(String, String)]>,
+    field_mask: FieldMask,
+    flat_location: bool,
+    max_record_len: usize,
+}
+
+impl SqliteSink {
+    /// 打开 `path` 处的数据库（不存在时自动创建），并创建 `logs` 表。
+    ///
+    /// `global_fields` 会与每条日志自身的键值对一起写入 `fields` 列，`field_mask` 匹配的键值对的值会被隐藏。
+    /// 调用位置同样写入 `fields` 列，`flat_location` 为是否写为扁平的 `file`、`line` 与 `column` 键而非嵌套的 `location` 对象。
+    /// `message` 列最多保留 `max_record_len` 字节。
+    pub(crate) fn open(
+        path: &str,
+        clock: Option<Clock>,
+        global_fields: Arc<[(String, String)]>,
+        field_mask: FieldMask,
+        flat_location: bool,
+        max_record_len: usize,
+    ) -> rusqlite::Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        let (sender, receiver) = mpsc::channel();
+        spawn_worker("sqlite", move |stop| write_rows(connection, receiver, stop));
+        Ok(SqliteSink {
+            sender,
+            clock,
+            global_fields,
+            field_mask,
+            flat_location,
+            max_record_len,
+        })
+    }
+}
+
+impl log::Log for SqliteSink {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let now = match (record_time(record), &self.clock) {
+            (Some(time), _) => time,
+            (None, Some(clock)) => clock(),
+            (None, None) => Local::now(),
+        };
+        let (module, location) = split_target(record.target());
+        // 优先使用目标中的位置信息（包含列号），其他来源的日志使用记录中的文件与行号
+        let location = location.and_then(parse_location).or_else(|| {
+            record
+                .file()
+                .zip(record.line())
+                .map(|(file, line)| (file, line, None))
+        });
+        let _ = self.sender.send(Message::Row(Row {
+            ts: now.to_rfc3339_opts(SecondsFormat::Millis, false),
+            level: record.level().as_str(),
+            target: module.to_string(),
+            file: record.file().map(str::to_string),
+            line: record.line(),
+            message: capped_message(record.args(), self.max_record_len),
+            fields: json::fields_object(
+                record,
+                location,
+                self.flat_location,
+                &self.global_fields,
+                &self.field_mask,
+            ),
+        }));
+    }
+
+    fn flush(&self) {
+        let (ack, done) = mpsc::channel();
+        if self.sender.send(Message::Flush(ack)).is_ok() {
+            let _ = done.recv_timeout(FLUSH_TIMEOUT);
+        }
+    }
+}
+
+/// 写入线程的主循环，每次将通道中积压的日志（最多 [`BATCH_SIZE`] 条）写入同一个事务。
+///
+/// 刷新请求在所在批次的事务提交后应答。收到停止信号且通道中没有积压的日志时退出。
+fn write_rows(mut connection: Connection, receiver: Receiver<Message>, stop: StopSignal) {
+    loop {
+        let first = match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(message) => message,
+            Err(RecvTimeoutError::Timeout) if stop.is_stopped() => break,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let mut rows = Vec::new();
+        let mut acks = Vec::new();
+        for message in std::iter::once(first).chain(receiver.try_iter().take(BATCH_SIZE - 1)) {
+            match message {
+                Message::Row(row) => rows.push(row),
+                Message::Flush(ack) => acks.push(ack),
+            }
+        }
+        if !rows.is_empty() {
+            if let Err(e) = insert_rows(&mut connection, rows) {
+                eprintln!("CLogger 写入 SQLite 数据库时出现错误: {}", e);
+            }
+        }
+        for ack in acks {
+            let _ = ack.send(());
+        }
+    }
+}
+
+/// 在同一个事务中写入 `rows`。
+fn insert_rows(connection: &mut Connection, rows: Vec<Row>) -> rusqlite::Result<()> {
+    let transaction = connection.transaction()?;
+    {
+        let mut insert = transaction.prepare_cached(
+            "INSERT INTO logs (ts, level, target, file, line, message, fields)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        for row in rows {
+            insert.execute(params![
+                row.ts,
+                row.level,
+                row.target,
+                row.file,
+                row.line,
+                row.message,
+                row.fields
+            ])?;
+        }
+    }
+    transaction.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::DEFAULT_MAX_RECORD_LEN;
+    use log::Log;
+
+    #[test]
+    fn test_sqlite_sink() {
+        let path = std::env::temp_dir().join(format!("clogger_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = SqliteSink::open(
+            path.to_str().unwrap(),
+            None,
+            Arc::from([("env".to_string(), "prod".to_string())]),
+            FieldMask::new(&["token".to_string()], false),
+            false,
+            DEFAULT_MAX_RECORD_LEN,
+        )
+        .unwrap();
+
+        sink.log(
+            &log::Record::builder()
+                .level(log::Level::Error)
+                .target("clogger::tests (src/sqlite.rs:1^1)")
+                .file(Some("src/sqlite.rs"))
+                .line(Some(1))
+                .key_values(&[("user", "chengcheng"), ("Token", "secret")])
+                .args(format_args!("这是一条错误日志！"))
+                .build(),
+        );
+        // 刷新会等待后台线程提交积压的日志
+        sink.flush();
+
+        let connection = Connection::open(&path).unwrap();
+        let (level, target, line, message, fields): (String, String, u32, String, String) =
+            connection
+                .query_row(
+                    "SELECT level, target, line, message, fields FROM logs",
+                    [],
+                    |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                        ))
+                    },
+                )
+                .unwrap();
+        assert_eq!(level, "ERROR");
+        assert_eq!(target, "clogger::tests");
+        assert_eq!(line, 1);
+        assert_eq!(message, "这是一条错误日志！");
+        assert_eq!(
+            fields,
+            r#"{"location":{"file":"src/sqlite.rs","line":1,"column":1},"user":"chengcheng","Token":"***","env":"prod"}"#
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,420 @@
+use colored::Color;
+use std::borrow::Cow;
+use unicode_width::UnicodeWidthStr;
+
+/// 文本样式，由颜色与字体效果组成。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{Color, Style};
+///
+/// let style = Style::new().color(Color::Cyan).dimmed(); // 暗淡的青色
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    /// 文本颜色，为 `None` 时不着色。
+    pub color: Option<Color>,
+    /// 是否加粗。
+    pub bold: bool,
+    /// 是否暗淡显示。
+    pub dimmed: bool,
+    /// 是否添加下划线。
+    pub underline: bool,
+}
+
+impl Style {
+    /// 创建一个不带任何样式的 `Style`。
+    pub const fn new() -> Self {
+        Style {
+            color: None,
+            bold: false,
+            dimmed: false,
+            underline: false,
+        }
+    }
+
+    /// 设置文本颜色。
+    pub const fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// 设置为加粗。
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// 设置为暗淡显示。
+    pub const fn dimmed(mut self) -> Self {
+        self.dimmed = true;
+        self
+    }
+
+    /// 设置为添加下划线。
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// 将样式应用到文本上，返回带有 ANSI 转义序列的文本。
+    ///
+    /// 与 `colored` 不同，该方法不受 `colored::control` 全局设置（基于标准输出是否为终端）的影响，
+    /// 是否着色由调用方根据各输出端自身的情况决定。
+    pub(crate) fn paint<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut codes: Vec<Cow<'static, str>> = Vec::new();
+        for (enabled, code) in [(self.bold, "1"), (self.dimmed, "2"), (self.underline, "4")] {
+            if enabled {
+                codes.push(Cow::Borrowed(code));
+            }
+        }
+        if let Some(color) = self.color {
+            codes.push(color.to_fg_str());
+        }
+        if codes.is_empty() || text.is_empty() {
+            return Cow::Borrowed(text);
+        }
+        let prefix = format!("\x1b[{}m", codes.join(";"));
+        // 文本中已有的重置序列之后重新应用样式，使嵌套的彩色文本不会中断外层的样式
+        let text = text.replace(RESET, &format!("{}{}", RESET, prefix));
+        Cow::Owned(format!("{}{}{}", prefix, text, RESET))
+    }
+}
+
+/// 重置所有样式的 ANSI 转义序列。
+const RESET: &str = "\x1b[0m";
+
+/// 将颜色编码为整数，以便作为日志记录的键值对传递给格式化程序。
+///
+/// 命名颜色编码为其序号，真彩色编码为 `0x1RRGGBB`。
+#[doc(hidden)]
+pub fn encode_color(color: Color) -> u64 {
+    match color {
+        Color::TrueColor { r, g, b } => 0x100_0000 | (r as u64) << 16 | (g as u64) << 8 | b as u64,
+        color => NAMED_COLORS
+            .iter()
+            .position(|named| *named == color)
+            .unwrap_or(0) as u64,
+    }
+}
+
+/// 解码由 [`encode_color`] 编码的颜色。
+pub(crate) fn decode_color(code: u64) -> Option<Color> {
+    if code & 0x100_0000 != 0 {
+        let [_, _, _, _, _, r, g, b] = code.to_be_bytes();
+        return Some(Color::TrueColor { r, g, b });
+    }
+    NAMED_COLORS.get(code as usize).copied()
+}
+
+/// 按序号排列的命名颜色。
+const NAMED_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+/// 单个日志级别的样式。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{ColorTheme, LevelStyle};
+///
+/// // 使用符号代替字母作为警告日志的级别标记
+/// let theme = ColorTheme {
+///     warn: LevelStyle {
+///         marker: "⚠".into(),
+///         ..ColorTheme::default().warn
+///     },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LevelStyle {
+    /// 级别标记的内容，默认为 `I`、`W`、`E`、`D`、`T`。标记会出现在所有输出端中。
+    pub marker: Cow<'static, str>,
+    /// 级别标记的样式。
+    pub marker_style: Style,
+    /// 日志信息内容的样式。
+    pub message: Style,
+    /// 是否为该级别的日志着色。为 `false` 时整行日志（包括时间戳与模块名称）都不着色，与全局的着色设置无关。
+    pub colored: bool,
+}
+
+/// CLogger 在终端输出时使用的配色主题。
+///
+/// 主题中的颜色与字体效果只会应用在终端的输出中，写入日志文件的内容始终不带颜色。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{CloggerBuilder, Color, ColorTheme, Style};
+///
+/// // 暗淡显示时间戳与模块名称，使日志级别与内容更加醒目
+/// let theme = ColorTheme {
+///     timestamp: Style::new().color(Color::Cyan).dimmed(),
+///     target: Style::new().color(Color::Magenta).dimmed(),
+///     ..Default::default()
+/// };
+/// CloggerBuilder::new()
+///     .log_file("/tmp/clogger_example.log")
+///     .theme(theme)
+///     .init();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColorTheme {
+    /// 时间戳的样式。
+    pub timestamp: Style,
+    /// 模块名称及位置信息的样式。
+    pub target: Style,
+    /// `Error` 级别的样式。
+    pub error: LevelStyle,
+    /// `Warn` 级别的样式。
+    pub warn: LevelStyle,
+    /// `Info` 级别的样式。
+    pub info: LevelStyle,
+    /// `Debug` 级别的样式。
+    pub debug: LevelStyle,
+    /// `Trace` 级别的样式。
+    pub trace: LevelStyle,
+}
+
+impl ColorTheme {
+    /// 获取指定日志级别的样式。
+    pub fn level(&self, level: log::Level) -> &LevelStyle {
+        match level {
+            log::Level::Error => &self.error,
+            log::Level::Warn => &self.warn,
+            log::Level::Info => &self.info,
+            log::Level::Debug => &self.debug,
+            log::Level::Trace => &self.trace,
+        }
+    }
+
+    /// 取消指定级别的着色，其他级别保持不变。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{CloggerBuilder, ColorTheme};
+    /// use log::Level;
+    ///
+    /// // 只为错误日志着色，其他级别的日志均不着色
+    /// let theme = [Level::Warn, Level::Info, Level::Debug, Level::Trace]
+    ///     .into_iter()
+    ///     .fold(ColorTheme::default(), ColorTheme::uncolored);
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .theme(theme)
+    ///     .init();
+    /// ```
+    ///
+    /// # 参数
+    /// - `level`: 不着色的日志级别。
+    pub fn uncolored(mut self, level: log::Level) -> Self {
+        match level {
+            log::Level::Error => self.error.colored = false,
+            log::Level::Warn => self.warn.colored = false,
+            log::Level::Info => self.info.colored = false,
+            log::Level::Debug => self.debug.colored = false,
+            log::Level::Trace => self.trace.colored = false,
+        }
+        self
+    }
+
+    /// 对色觉障碍（尤其是红绿色盲）用户友好的配色主题。
+    ///
+    /// 使用 Okabe-Ito 色板中易于区分的蓝色与橙色系颜色，并通过加粗、下划线与暗淡显示进一步区分各级别，
+    /// 即使无法分辨颜色也能识别错误与警告日志。该主题使用 24 位真彩色，需要终端支持。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use clogger::{CloggerBuilder, ColorTheme};
+    ///
+    /// CloggerBuilder::new()
+    ///     .log_file("/tmp/clogger_example.log")
+    ///     .theme(ColorTheme::colorblind_friendly())
+    ///     .init();
+    /// ```
+    pub fn colorblind_friendly() -> Self {
+        const VERMILLION: Color = Color::TrueColor {
+            r: 213,
+            g: 94,
+            b: 0,
+        };
+        const ORANGE: Color = Color::TrueColor {
+            r: 230,
+            g: 159,
+            b: 0,
+        };
+        const BLUE: Color = Color::TrueColor {
+            r: 0,
+            g: 114,
+            b: 178,
+        };
+        const SKY_BLUE: Color = Color::TrueColor {
+            r: 86,
+            g: 180,
+            b: 233,
+        };
+        const PURPLE: Color = Color::TrueColor {
+            r: 204,
+            g: 121,
+            b: 167,
+        };
+        ColorTheme {
+            timestamp: Style::new().color(SKY_BLUE).dimmed(),
+            target: Style::new().color(PURPLE),
+            // 错误日志为加粗并带下划线的朱红色
+            error: LevelStyle {
+                marker: Cow::Borrowed("E"),
+                marker_style: Style::new().color(VERMILLION).bold().underline(),
+                message: Style::new().color(VERMILLION).bold(),
+                colored: true,
+            },
+            // 警告日志为加粗的橙色
+            warn: LevelStyle {
+                marker: Cow::Borrowed("W"),
+                marker_style: Style::new().color(ORANGE).bold(),
+                message: Style::new().color(ORANGE),
+                colored: true,
+            },
+            // 普通日志为蓝色
+            info: LevelStyle {
+                marker: Cow::Borrowed("I"),
+                marker_style: Style::new().color(BLUE),
+                message: Style::new(),
+                colored: true,
+            },
+            // 调试日志为暗淡的天蓝色
+            debug: LevelStyle {
+                marker: Cow::Borrowed("D"),
+                marker_style: Style::new().color(SKY_BLUE).dimmed(),
+                message: Style::new(),
+                colored: true,
+            },
+            // 追踪日志为暗淡的紫色
+            trace: LevelStyle {
+                marker: Cow::Borrowed("T"),
+                marker_style: Style::new().color(PURPLE).dimmed(),
+                message: Style::new().dimmed(),
+                colored: true,
+            },
+        }
+    }
+
+    /// 获取所有级别标记中最大的显示宽度。
+    pub(crate) fn marker_width(&self) -> usize {
+        [
+            &self.error,
+            &self.warn,
+            &self.info,
+            &self.debug,
+            &self.trace,
+        ]
+        .iter()
+        .map(|style| style.marker.width())
+        .max()
+        .unwrap_or(0)
+    }
+}
+
+impl Default for ColorTheme {
+    fn default() -> Self {
+        ColorTheme {
+            timestamp: Style::new().color(Color::Cyan),
+            target: Style::new().color(Color::Magenta),
+            // 错误日志为红色
+            error: LevelStyle {
+                marker: Cow::Borrowed("E"),
+                marker_style: Style::new().color(Color::Red),
+                message: Style::new().color(Color::Red),
+                colored: true,
+            },
+            // 警告日志为黄色
+            warn: LevelStyle {
+                marker: Cow::Borrowed("W"),
+                marker_style: Style::new().color(Color::Yellow),
+                message: Style::new().color(Color::Yellow),
+                colored: true,
+            },
+            // 普通日志为绿色
+            info: LevelStyle {
+                marker: Cow::Borrowed("I"),
+                marker_style: Style::new().color(Color::Green),
+                message: Style::new(),
+                colored: true,
+            },
+            // 调试日志为蓝色
+            debug: LevelStyle {
+                marker: Cow::Borrowed("D"),
+                marker_style: Style::new().color(Color::Blue),
+                message: Style::new(),
+                colored: true,
+            },
+            // 追踪日志为紫色
+            trace: LevelStyle {
+                marker: Cow::Borrowed("T"),
+                marker_style: Style::new().color(Color::Magenta),
+                message: Style::new(),
+                colored: true,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_color() {
+        for color in NAMED_COLORS
+            .into_iter()
+            .chain([Color::TrueColor { r: 1, g: 2, b: 3 }])
+        {
+            assert_eq!(decode_color(encode_color(color)), Some(color));
+        }
+    }
+
+    #[test]
+    fn test_paint() {
+        let style = Style::new().color(Color::Red).bold().underline();
+        assert_eq!(style.paint("错误"), "\x1b[1;4;31m错误\x1b[0m");
+        assert_eq!(Style::new().paint("普通"), "普通");
+    }
+
+    #[test]
+    fn test_colorblind_friendly_levels_distinct() {
+        let theme = ColorTheme::colorblind_friendly();
+        let styles: Vec<_> = [
+            &theme.error,
+            &theme.warn,
+            &theme.info,
+            &theme.debug,
+            &theme.trace,
+        ]
+        .iter()
+        .map(|style| style.marker_style)
+        .collect();
+        // 每个级别的标记样式互不相同，错误与警告日志不只依靠颜色区分
+        for (i, a) in styles.iter().enumerate() {
+            for b in &styles[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        assert!(theme.error.marker_style.bold && theme.error.marker_style.underline);
+        assert!(theme.warn.marker_style.bold);
+    }
+}
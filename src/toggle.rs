@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// CLogger 是否处于启用状态。
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// CLogger 是否已被 [`shutdown`](crate::shutdown) 关闭。
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// CLogger 是否已被设置为全局的 `log` 日志器。
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// 通过 [`set_colors`] 设置的终端着色状态，取值为 [`COLORS_AUTO`]、[`COLORS_OFF`] 或 [`COLORS_ON`]。
+static COLORS: AtomicU8 = AtomicU8::new(COLORS_AUTO);
+
+/// 未调用 [`set_colors`]，沿用初始化时的判断。
+const COLORS_AUTO: u8 = 0;
+/// 终端输出不着色。
+const COLORS_OFF: u8 = 1;
+/// 终端输出着色。
+const COLORS_ON: u8 = 2;
+
+/// 暂停 CLogger 的所有输出。
+///
+/// 暂停期间所有日志都会被直接丢弃，适合在性能敏感的代码段中临时静默日志。
+/// 与修改日志级别不同，暂停不会影响已配置的级别，调用 [`enable`] 即可恢复原来的输出。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_log, init_clogger};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// clogger::disable();
+/// for i in 0..1000 {
+///     c_log!("example::moudle_name", format!("这条日志不会被输出: {}", i));
+/// }
+/// clogger::enable();
+/// ```
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// 恢复被 [`disable`] 暂停的输出。
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// 获取 CLogger 当前是否处于启用状态。
+///
+/// 被 [`disable`] 暂停或被 [`shutdown`](crate::shutdown) 关闭时返回 `false`。
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) && !SHUT_DOWN.load(Ordering::Relaxed)
+}
+
+/// 标记 CLogger 已被关闭，之后的日志都会被丢弃。
+pub(crate) fn mark_shut_down() {
+    SHUT_DOWN.store(true, Ordering::Relaxed);
+}
+
+/// 获取 CLogger 是否已经完成初始化（通过 [`init_clogger`](crate::init_clogger) 或 [`CloggerBuilder::init`](crate::CloggerBuilder::init)）。
+///
+/// CLogger 只会被初始化一次，之后的初始化调用都会被忽略。库可以据此判断宿主程序是否已经初始化了 CLogger，
+/// 避免用自己的默认配置覆盖（或被忽略）宿主程序的配置。通过 [`CloggerBuilder::build`](crate::CloggerBuilder::build)
+/// 创建的独立实例不会影响该状态。
+///
+/// # 示例
+/// ```rust
+/// use clogger::init_clogger;
+///
+/// if !clogger::is_initialized() {
+///     init_clogger("/tmp/clogger_example.log");
+/// }
+/// assert!(clogger::is_initialized());
+/// ```
+pub fn is_initialized() -> bool {
+    INITIALIZED.load(Ordering::Acquire)
+}
+
+/// 标记 CLogger 已完成初始化。
+pub(crate) fn mark_initialized() {
+    INITIALIZED.store(true, Ordering::Release);
+}
+
+/// 在运行时开启或关闭终端输出的着色。
+///
+/// CLogger 默认在初始化时根据 `NO_COLOR` 等环境变量与输出流是否为终端决定是否着色（见 [`CloggerBuilder::plain`](crate::CloggerBuilder::plain)），
+/// 调用该函数后，之后的每条日志都会按照此处的设置输出到标准输出与标准错误，无需重新初始化，
+/// 适合在 REPL 等交互式程序中提供切换颜色的命令。该设置会覆盖初始化时的判断（包括 `plain(true)`），
+/// 但不会影响日志文件等其他输出端，它们始终不带颜色；路由表中设置为 [`SinkFormat::plain`](crate::SinkFormat::plain) 的终端输出同样保持不着色。
+/// 该设置同时作用于全局 CLogger 与通过 [`CloggerBuilder::build`](crate::CloggerBuilder::build) 创建的独立实例。
+///
+/// # 示例
+/// ```rust
+/// use clogger::{c_log, init_clogger};
+///
+/// init_clogger("/tmp/clogger_example.log");
+/// clogger::set_colors(false); // 例如用户输入了 `:color off`
+/// c_log!("example::moudle_name", "这条日志不带颜色");
+/// clogger::set_colors(true);
+/// c_log!("example::moudle_name", "这条日志带有颜色");
+/// ```
+///
+/// # 参数
+/// - `enabled`: 是否为终端输出着色。
+pub fn set_colors(enabled: bool) {
+    let colors = if enabled { COLORS_ON } else { COLORS_OFF };
+    COLORS.store(colors, Ordering::Relaxed);
+}
+
+/// 获取通过 [`set_colors`] 设置的着色状态，未设置时返回 `None`。
+pub(crate) fn colors_override() -> Option<bool> {
+    match COLORS.load(Ordering::Relaxed) {
+        COLORS_OFF => Some(false),
+        COLORS_ON => Some(true),
+        _ => None,
+    }
+}
@@ -0,0 +1,71 @@
+use log::LevelFilter;
+use std::cell::Cell;
+
+thread_local! {
+    /// 当前线程中由 [`VerboseGuard`] 临时提高的日志级别。
+    static LEVEL_OVERRIDE: Cell<Option<LevelFilter>> = const { Cell::new(None) };
+}
+
+/// 获取考虑当前线程临时提高的级别后，`level` 实际生效的日志级别。
+pub(crate) fn effective_level(level: LevelFilter) -> LevelFilter {
+    LEVEL_OVERRIDE
+        .with(Cell::get)
+        .map_or(level, |boost| level.max(boost))
+}
+
+/// 临时提高当前线程日志级别的 RAII 守卫，通常通过 [`c_verbose_scope!`](crate::c_verbose_scope) 创建。
+///
+/// 守卫存在期间，当前线程的日志级别至少为指定的级别，离开作用域时恢复；其他线程的日志级别不受影响。
+/// 各输出端自身的级别（如 [`CloggerBuilder::stdout_level`](crate::CloggerBuilder::stdout_level)）仍然有效。
+///
+/// 为了让被提高级别的日志通过 `log` 宏的检查，守卫会放宽 `log` 的全局最大级别，且离开作用域后不会恢复；
+/// 此后其他线程中低于配置级别的日志仍会被 CLogger 丢弃，只是多了一次级别判断的开销。
+pub struct VerboseGuard {
+    previous: Option<LevelFilter>,
+}
+
+impl VerboseGuard {
+    /// 将当前线程的日志级别提高到 `level`。
+    ///
+    /// # 参数
+    /// - `level`: 守卫存在期间的最低日志级别。
+    #[doc(hidden)]
+    pub fn new(level: LevelFilter) -> Self {
+        let previous = LEVEL_OVERRIDE.with(|o| o.replace(Some(effective_level(level))));
+        // `log` 宏会先与全局的最大级别比较，需要放宽该级别才能让被提高级别的日志到达 CLogger
+        if level > log::max_level() {
+            log::set_max_level(level);
+        }
+        VerboseGuard { previous }
+    }
+}
+
+impl Drop for VerboseGuard {
+    fn drop(&mut self) {
+        LEVEL_OVERRIDE.with(|o| o.set(self.previous));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbose_guard() {
+        assert_eq!(effective_level(LevelFilter::Info), LevelFilter::Info);
+        {
+            let _outer = VerboseGuard::new(LevelFilter::Trace);
+            assert_eq!(effective_level(LevelFilter::Info), LevelFilter::Trace);
+            {
+                // 嵌套的守卫不会降低外层提高的级别
+                let _inner = VerboseGuard::new(LevelFilter::Debug);
+                assert_eq!(effective_level(LevelFilter::Info), LevelFilter::Trace);
+            }
+            assert_eq!(effective_level(LevelFilter::Info), LevelFilter::Trace);
+            // 其他线程不受影响
+            let other = std::thread::spawn(|| effective_level(LevelFilter::Info));
+            assert_eq!(other.join().unwrap(), LevelFilter::Info);
+        }
+        assert_eq!(effective_level(LevelFilter::Info), LevelFilter::Info);
+    }
+}
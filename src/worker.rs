@@ -0,0 +1,132 @@
+use crate::shutdown::{spawn_worker, POLL_INTERVAL};
+use std::io::{self, Write};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+/// 刷新时等待后台线程写入积压日志的最长时间。
+pub(crate) const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 发送给后台写入线程的消息。
+enum Message {
+    /// 一条完整的日志及写入后是否同步到存储设备。
+    Line(Vec<u8>, bool),
+    /// 写入之前的所有日志后通过该发送端通知。
+    Flush(Sender<()>),
+}
+
+/// 将日志交给后台线程写入的写入端，由 [`spawn_writer`] 创建。
+///
+/// 写入的内容暂存在写入端中，fern 在每条日志之后调用 `flush` 时作为一条完整的日志发送给后台线程，
+/// 因此发送端不会阻塞。没有暂存内容时调用 `flush`（如 [`flush_clogger`](crate::flush_clogger)）
+/// 会等待后台线程写入所有积压的日志，最多等待 [`FLUSH_TIMEOUT`]。
+pub(crate) struct WorkerWriter {
+    sender: Sender<Message>,
+    pending: Vec<u8>,
+}
+
+impl WorkerWriter {
+    /// 将一条完整的日志发送给后台线程，`sync` 为写入后是否同步到存储设备。
+    ///
+    /// 后台线程已经退出（CLogger 已被关闭）时丢弃日志。
+    pub(crate) fn send_line(&self, line: Vec<u8>, sync: bool) {
+        let _ = self.sender.send(Message::Line(line, sync));
+    }
+
+    /// 等待后台线程写入所有已发送的日志，后台线程已经退出时立即返回。
+    pub(crate) fn drain(&self) {
+        let (ack, done) = mpsc::channel();
+        if self.sender.send(Message::Flush(ack)).is_ok() {
+            let _ = done.recv_timeout(FLUSH_TIMEOUT);
+        }
+    }
+}
+
+impl Write for WorkerWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            self.drain();
+        } else {
+            let line = std::mem::take(&mut self.pending);
+            self.send_line(line, false);
+        }
+        Ok(())
+    }
+}
+
+/// 启动一个后台线程，将通过返回的 [`WorkerWriter`] 发送的日志写入 `writer`。
+///
+/// 发送端不会阻塞，实际的 I/O 操作全部在后台线程中完成。
+/// [`shutdown`](crate::shutdown) 时，线程会在写入所有积压的日志后退出。
+pub(crate) fn spawn_writer<W: Write + Send + 'static>(name: &str, mut writer: W) -> WorkerWriter {
+    spawn_line_writer(name, move |line, _| {
+        let _ = writer.write_all(line);
+        let _ = writer.flush();
+    })
+}
+
+/// 启动一个后台线程，对通过返回的 [`WorkerWriter`] 发送的每条日志调用 `write_line`。
+///
+/// `write_line` 的第二个参数为写入后是否同步到存储设备，用于按级别同步的日志文件。
+pub(crate) fn spawn_line_writer<F>(name: &str, mut write_line: F) -> WorkerWriter
+where
+    F: FnMut(&[u8], bool) + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<Message>();
+    spawn_worker(name, move |stop| loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(Message::Line(line, sync)) => write_line(&line, sync),
+            Ok(Message::Flush(ack)) => {
+                let _ = ack.send(());
+            }
+            // 通道为空时才检查停止信号，保证积压的日志都已写入
+            Err(RecvTimeoutError::Timeout) if stop.is_stopped() => break,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    });
+    WorkerWriter {
+        sender,
+        pending: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// 写入较慢的写入端，用于模拟积压的日志。
+    #[derive(Clone, Default)]
+    struct SlowWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SlowWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            std::thread::sleep(Duration::from_millis(20));
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_flush_drains() {
+        let output = SlowWriter::default();
+        let mut writer = spawn_writer("test", output.clone());
+        for i in 0..5 {
+            writeln!(writer, "第 {} 条", i).unwrap();
+            writer.flush().unwrap();
+        }
+        // 没有暂存内容时刷新会等待积压的日志全部写入
+        writer.flush().unwrap();
+        let contents = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(contents.lines().count(), 5, "{}", contents);
+    }
+}
@@ -0,0 +1,68 @@
+//! 多个线程同时初始化 CLogger 并输出日志的压力测试。
+//!
+//! 全局 CLogger 只能初始化一次，因此该测试单独作为一个测试程序运行，保证由这里的 `init_clogger` 完成初始化。
+
+use clogger::{c_log, current_log_path, flush_clogger, init_clogger, log_count};
+use log::Level;
+use std::sync::{mpsc, Arc, Barrier};
+use std::time::Duration;
+
+const THREADS: usize = 16;
+const LINES: usize = 50;
+
+#[test]
+fn test_concurrent_init() {
+    let path = std::env::temp_dir().join(format!(
+        "clogger_concurrent_init_{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    // 所有线程同时初始化并输出日志，在超时之前未完成即视为死锁
+    let (done, finished) = mpsc::channel();
+    let log_path = path.clone();
+    std::thread::spawn(move || {
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let barrier = barrier.clone();
+                let log_path = log_path.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    init_clogger(log_path.to_str().unwrap());
+                    for line in 0..LINES {
+                        c_log!(format!("并发初始化 {}-{}", thread, line));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        done.send(()).unwrap();
+    });
+    finished
+        .recv_timeout(Duration::from_secs(30))
+        .expect("并发初始化与输出日志时出现死锁");
+    flush_clogger();
+
+    // 除了各线程的日志之外，只有一条初始化完成的日志
+    assert_eq!(log_count(Level::Info), (THREADS * LINES) as u64 + 1);
+    // `CLOGGER_FILE` 环境变量可能覆盖了日志文件的位置
+    let active = current_log_path().expect("CLogger 没有使用日志文件");
+    let content = std::fs::read_to_string(&active).unwrap();
+    assert_eq!(content.matches("并发初始化 ").count(), THREADS * LINES);
+    for thread in 0..THREADS {
+        for line in 0..LINES {
+            let message = format!("并发初始化 {}-{}", thread, line);
+            assert!(
+                content.contains(&format!("{}\n", message)),
+                "缺少日志: {}",
+                message
+            );
+        }
+    }
+    if active == path {
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,52 @@
+//! 启用非阻塞写入时，运行时修改配置写入的元数据行与日志之间的顺序测试。
+//!
+//! 全局 CLogger 只能初始化一次，因此该测试单独作为一个测试程序运行。
+
+use clogger::{c_log, current_log_path, flush_clogger, set_file_level, CloggerBuilder};
+use log::LevelFilter;
+
+const LINES: usize = 500;
+
+#[test]
+fn test_format_change_after_queued_logs() {
+    let path = std::env::temp_dir().join(format!(
+        "clogger_format_change_order_{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    CloggerBuilder::new()
+        .log_file(path.to_str().unwrap())
+        .stdout_level(LevelFilter::Off)
+        .non_blocking(true)
+        .init();
+
+    for line in 0..LINES {
+        c_log!(format!("修改级别之前 {}", line));
+    }
+    // 元数据行排在后台线程中积压的日志之后
+    set_file_level(LevelFilter::Debug);
+    c_log!("修改级别之后");
+    flush_clogger();
+
+    // `CLOGGER_FILE` 环境变量可能覆盖了日志文件的位置
+    let active = current_log_path().expect("CLogger 没有使用日志文件");
+    let content = std::fs::read_to_string(&active).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    let meta = lines
+        .iter()
+        .position(|line| {
+            line.starts_with(r#"{"_clogger_meta":"format_change","change":"file_level""#)
+        })
+        .expect("缺少元数据行");
+    let before = lines[..meta]
+        .iter()
+        .filter(|line| line.contains("修改级别之前 "))
+        .count();
+    assert_eq!(before, LINES);
+    assert!(lines[meta + 1..]
+        .iter()
+        .any(|line| line.ends_with("修改级别之后")));
+    if active == path {
+        std::fs::remove_file(&path).unwrap();
+    }
+}